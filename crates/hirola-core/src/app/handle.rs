@@ -0,0 +1,98 @@
+//! Handles to mounted apps.
+use crate::dom::Dom;
+use wasm_bindgen::JsCast;
+
+/// A mounted [`App`](super::App), returned by [`App::mount`](super::App::mount) and friends.
+///
+/// The handle keeps the rendered tree alive. Once unmounted or dropped, the nodes rendered by the app
+/// are removed, effects are cancelled and the router's global listeners and the plugins are cleaned
+/// up. Other children of the parent are left in place. Use [`AppHandle::forget`] for apps that live
+/// as long as the page.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+///
+/// # Example
+///
+/// ```no_run
+/// use hirola::prelude::*;
+///
+/// let app = App::new(());
+/// let handle = app.mount();
+/// // ... later, eg. on hot reload ...
+/// handle.unmount();
+/// ```
+#[must_use = "the app is unmounted when the handle is dropped, call `forget` to keep it mounted"]
+pub struct AppHandle {
+    root: Option<Dom>,
+    parent: web_sys::Node,
+    /// The nodes inserted into the parent, the router removes its pages itself.
+    nodes: Vec<web_sys::Node>,
+}
+
+impl AppHandle {
+    /// Creates a handle for a router rendered into `parent`.
+    pub(crate) fn new(root: Dom, parent: &web_sys::Node) -> Self {
+        Self {
+            root: Some(root),
+            parent: parent.clone(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Creates a handle for `root`, which is about to be appended to `parent`.
+    pub(crate) fn appended(root: Dom, parent: &web_sys::Node) -> Self {
+        let node = crate::generic_node::as_dom(root.node()).inner_element();
+        let nodes = match node.dyn_ref::<web_sys::DocumentFragment>() {
+            // The children of fragments are moved into the parent
+            Some(fragment) => {
+                std::iter::successors(fragment.first_child(), |child| child.next_sibling())
+                    .collect()
+            }
+            None => vec![node],
+        };
+        Self {
+            root: Some(root),
+            parent: parent.clone(),
+            nodes,
+        }
+    }
+
+    /// Returns the root of the rendered tree.
+    pub fn root(&self) -> &Dom {
+        self.root.as_ref().unwrap()
+    }
+
+    /// Unmounts the app, same as dropping the handle.
+    pub fn unmount(self) {}
+
+    /// Keeps the app mounted for the lifetime of the page.
+    pub fn forget(mut self) {
+        // We leak the root node to avoid callbacks and futures being dropped
+        std::mem::forget(self.root.take());
+    }
+}
+
+impl std::fmt::Debug for AppHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppHandle")
+            .field("parent", &self.parent)
+            .field("nodes", &self.nodes)
+            .finish()
+    }
+}
+
+impl Drop for AppHandle {
+    fn drop(&mut self) {
+        let Some(root) = self.root.take() else {
+            return;
+        };
+        // Discarding the root cancels its effects and runs cleanups, eg. removing router listeners
+        // and the pages it rendered
+        drop(root);
+        for node in &self.nodes {
+            if node.parent_node().as_ref() == Some(&self.parent) {
+                let _ = self.parent.remove_child(node);
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! History backends used by the [`Router`](super::router::Router) to read, push and listen to locations.
+//!
+//! - [`BrowserHistory`] uses the HTML5 history API with an optional base path (eg. `/app/about`).
+//! - [`HashHistory`] keeps the route in the URL fragment (eg. `/#/about`), useful for static file servers.
+//! - [`MemoryHistory`] keeps the history stack in memory and works without a browser.
+use std::{cell::RefCell, fmt, rc::Rc};
+#[cfg(feature = "dom")]
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+
+/// A callback invoked with the new route path when the location changes outside the router.
+pub type HistoryListener = Rc<dyn Fn(String)>;
+
+/// A function that stops a listener registered with [`HistoryBackend::listen`].
+pub type Unlisten = Box<dyn FnOnce()>;
+
+/// Trait describing where the [`Router`](super::router::Router) reads and writes its locations.
+///
+/// # Example
+///
+/// ```no_run
+/// use hirola::prelude::*;
+/// use hirola::prelude::history::MemoryHistory;
+///
+/// let mut app = App::new(());
+/// app.set_history(MemoryHistory::new("/"));
+/// ```
+pub trait HistoryBackend: fmt::Debug {
+    /// Returns the current route path, without any base path or hash prefix.
+    fn location(&self) -> String;
+
+    /// Pushes a new entry for `path` onto the history stack.
+    fn push(&self, path: &str);
+
+    /// Replaces the current history entry with `path`.
+    fn replace(&self, path: &str);
+
+    /// Returns the position of the current entry in the history stack.
+    ///
+    /// The router uses it to undo moves blocked by its hooks. Backends that cannot tell their
+    /// position return `0`, blocked moves are then undone by replacing the entry.
+    fn index(&self) -> usize {
+        0
+    }
+
+    /// Moves `delta` entries backwards (negative) or forwards (positive), notifying the listeners.
+    fn go(&self, delta: isize) {
+        let _ = delta;
+    }
+
+    /// Returns the `href` that links to `path` should use.
+    fn href(&self, path: &str) -> String {
+        path.to_string()
+    }
+
+    /// Starts listening for location changes that do not come from the router, eg. the back button.
+    ///
+    /// Several listeners can be registered at once. The returned function removes `listener`.
+    fn listen(&self, listener: HistoryListener) -> Unlisten;
+}
+
+/// Adds a listener for `event` on the window, returning a function that removes it.
+#[cfg(feature = "dom")]
+pub(crate) fn listen_window(
+    event: &'static str,
+    handler: impl Fn(web_sys::Event) + 'static,
+) -> Unlisten {
+    let closure = Closure::wrap(Box::new(handler) as Box<dyn Fn(web_sys::Event)>);
+    let window = web_sys::window().unwrap();
+    window
+        .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+        .unwrap();
+    Box::new(move || {
+        let _ = window.remove_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+    })
+}
+
+/// Returns the position of the current browser history entry, stored in its state by
+/// [`push_state`].
+#[cfg(feature = "dom")]
+fn state_index() -> usize {
+    web_sys::window()
+        .unwrap()
+        .history()
+        .unwrap()
+        .state()
+        .ok()
+        .and_then(|state| state.as_f64())
+        .map_or(0, |index| index as usize)
+}
+
+/// Pushes or replaces the browser history entry with `url`, keeping track of its position.
+#[cfg(feature = "dom")]
+fn push_state(url: &str, replace: bool) {
+    let history = web_sys::window().unwrap().history().unwrap();
+    if replace {
+        let index = JsValue::from(state_index() as f64);
+        history
+            .replace_state_with_url(&index, "", Some(url))
+            .unwrap();
+    } else {
+        let index = JsValue::from((state_index() + 1) as f64);
+        history.push_state_with_url(&index, "", Some(url)).unwrap();
+    }
+}
+
+/// Moves `delta` entries in the browser history.
+#[cfg(feature = "dom")]
+fn go_browser(delta: isize) {
+    let _ = web_sys::window()
+        .unwrap()
+        .history()
+        .unwrap()
+        .go_with_delta(delta as i32);
+}
+
+/// Joins a base path and a route path, eg. `/app` and `/about` into `/app/about`.
+#[cfg(feature = "dom")]
+fn join_base(base: &str, path: &str) -> String {
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
+
+/// History backend using the HTML5 history API.
+///
+/// Routes are served under `base`, which allows mounting an app on a sub path of a site.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+#[cfg(feature = "dom")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrowserHistory {
+    /// The path prefix all routes are served under, eg. `/app`.
+    pub base: String,
+}
+
+#[cfg(feature = "dom")]
+impl BrowserHistory {
+    /// Creates a new browser history serving routes under `base`.
+    pub fn new(base: &str) -> Self {
+        Self {
+            base: base.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "dom")]
+impl HistoryBackend for BrowserHistory {
+    fn location(&self) -> String {
+        let path_name = web_sys::window()
+            .unwrap()
+            .location()
+            .pathname()
+            .unwrap_or_else(|_| "/".to_string());
+        let base = self.base.trim_end_matches('/');
+        // The base only matches whole segments, eg. `/app` does not match `/application`
+        match path_name.strip_prefix(base) {
+            Some("") => String::from("/"),
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => path_name,
+        }
+    }
+
+    fn push(&self, path: &str) {
+        push_state(&self.href(path), false);
+    }
+
+    fn replace(&self, path: &str) {
+        push_state(&self.href(path), true);
+    }
+
+    fn index(&self) -> usize {
+        state_index()
+    }
+
+    fn go(&self, delta: isize) {
+        go_browser(delta);
+    }
+
+    fn href(&self, path: &str) -> String {
+        join_base(&self.base, path)
+    }
+
+    fn listen(&self, listener: HistoryListener) -> Unlisten {
+        let history = self.clone();
+        let on_hash = Rc::clone(&listener);
+        //Hash routing forward in history and URL rewrite
+        let unlisten_hash = listen_window("hashchange", move |_evt: web_sys::Event| {
+            let l: String = web_sys::window()
+                .unwrap()
+                .location()
+                .hash()
+                .unwrap()
+                .chars()
+                .skip(1)
+                .collect();
+            // Other fragments are in-page anchors, scrolled to by the browser
+            if !l.starts_with('/') {
+                return;
+            }
+            log::debug!("hash handle : {l}");
+            history.replace(&l);
+            on_hash(l);
+        });
+
+        let history = self.clone();
+        //Routing for navigating in history and escaping hash routes
+        let unlisten_pop = listen_window("popstate", move |_evt: web_sys::Event| {
+            let location = web_sys::window().unwrap().location();
+            if location.hash().unwrap().starts_with("#/") {
+                log::debug!("hash route detected");
+                return;
+            }
+            let path_name = history.location();
+            log::debug!("pop handle : {path_name}");
+            listener(path_name);
+        });
+        Box::new(move || {
+            unlisten_hash();
+            unlisten_pop();
+        })
+    }
+}
+
+/// History backend storing the route in the URL fragment, eg. `/#/about`.
+///
+/// Since the server only ever sees `/`, this works on static file servers without rewrite rules.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+#[cfg(feature = "dom")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashHistory;
+
+#[cfg(feature = "dom")]
+impl HistoryBackend for HashHistory {
+    fn location(&self) -> String {
+        let hash = web_sys::window()
+            .unwrap()
+            .location()
+            .hash()
+            .unwrap_or_default();
+        match hash.trim_start_matches('#') {
+            "" => String::from("/"),
+            path => path.to_string(),
+        }
+    }
+
+    fn push(&self, path: &str) {
+        push_state(&self.href(path), false);
+    }
+
+    fn replace(&self, path: &str) {
+        push_state(&self.href(path), true);
+    }
+
+    fn index(&self) -> usize {
+        state_index()
+    }
+
+    fn go(&self, delta: isize) {
+        go_browser(delta);
+    }
+
+    fn href(&self, path: &str) -> String {
+        format!("#{path}")
+    }
+
+    fn listen(&self, listener: HistoryListener) -> Unlisten {
+        let history = self.clone();
+        listen_window("hashchange", move |_evt: web_sys::Event| {
+            let path = history.location();
+            log::debug!("hash handle : {path}");
+            listener(path);
+        })
+    }
+}
+
+#[derive(Default)]
+struct MemoryStack {
+    entries: Vec<String>,
+    index: usize,
+    listeners: Vec<HistoryListener>,
+}
+
+/// History backend keeping the history stack in memory.
+///
+/// This is the default backend when the `dom` feature is disabled, and is useful for testing
+/// router behaviour natively. Clones share the same stack.
+///
+/// # Example
+///
+/// ```
+/// use hirola::prelude::history::{HistoryBackend, MemoryHistory};
+///
+/// let history = MemoryHistory::new("/");
+/// history.push("/about");
+/// history.back();
+/// assert_eq!(history.location(), "/");
+/// history.forward();
+/// assert_eq!(history.location(), "/about");
+/// ```
+#[derive(Clone)]
+pub struct MemoryHistory {
+    stack: Rc<RefCell<MemoryStack>>,
+}
+
+impl MemoryHistory {
+    /// Creates a new in-memory history starting at `initial`.
+    pub fn new(initial: &str) -> Self {
+        Self {
+            stack: Rc::new(RefCell::new(MemoryStack {
+                entries: vec![initial.to_string()],
+                index: 0,
+                listeners: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns all the entries in the history stack.
+    pub fn entries(&self) -> Vec<String> {
+        self.stack.borrow().entries.clone()
+    }
+
+    /// Returns the index of the current entry in the history stack.
+    pub fn index(&self) -> usize {
+        self.stack.borrow().index
+    }
+
+    /// Moves `delta` entries backwards (negative) or forwards (positive) and notifies listeners.
+    /// Does nothing if the target entry does not exist.
+    pub fn go(&self, delta: isize) {
+        let (path, listeners) = {
+            let mut stack = self.stack.borrow_mut();
+            let index = stack.index as isize + delta;
+            if index < 0 || index >= stack.entries.len() as isize || delta == 0 {
+                return;
+            }
+            stack.index = index as usize;
+            (stack.entries[stack.index].clone(), stack.listeners.clone())
+        };
+        for listener in listeners {
+            listener(path.clone());
+        }
+    }
+
+    /// Moves to the previous entry, like the browser back button.
+    pub fn back(&self) {
+        self.go(-1)
+    }
+
+    /// Moves to the next entry, like the browser forward button.
+    pub fn forward(&self) {
+        self.go(1)
+    }
+}
+
+impl Default for MemoryHistory {
+    fn default() -> Self {
+        Self::new("/")
+    }
+}
+
+impl fmt::Debug for MemoryHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stack = self.stack.borrow();
+        f.debug_struct("MemoryHistory")
+            .field("entries", &stack.entries)
+            .field("index", &stack.index)
+            .finish()
+    }
+}
+
+impl HistoryBackend for MemoryHistory {
+    fn location(&self) -> String {
+        let stack = self.stack.borrow();
+        stack.entries[stack.index].clone()
+    }
+
+    fn push(&self, path: &str) {
+        let mut stack = self.stack.borrow_mut();
+        let index = stack.index + 1;
+        stack.entries.truncate(index);
+        stack.entries.push(path.to_string());
+        stack.index = index;
+    }
+
+    fn replace(&self, path: &str) {
+        let mut stack = self.stack.borrow_mut();
+        let index = stack.index;
+        stack.entries[index] = path.to_string();
+    }
+
+    fn index(&self) -> usize {
+        MemoryHistory::index(self)
+    }
+
+    fn go(&self, delta: isize) {
+        MemoryHistory::go(self, delta)
+    }
+
+    fn listen(&self, listener: HistoryListener) -> Unlisten {
+        self.stack.borrow_mut().listeners.push(Rc::clone(&listener));
+        let stack = Rc::downgrade(&self.stack);
+        Box::new(move || {
+            if let Some(stack) = stack.upgrade() {
+                stack
+                    .borrow_mut()
+                    .listeners
+                    .retain(|l| !Rc::ptr_eq(l, &listener));
+            }
+        })
+    }
+}
+
+/// The history backend used when none is set.
+/// [`BrowserHistory`] when rendering with the `dom` backend, else [`MemoryHistory`].
+pub(crate) fn default_history() -> Rc<dyn HistoryBackend> {
+    #[cfg(feature = "dom")]
+    if crate::generic_node::backend() == crate::generic_node::Backend::Dom
+        && web_sys::window().is_some()
+    {
+        return Rc::new(BrowserHistory::default());
+    }
+    Rc::new(MemoryHistory::default())
+}
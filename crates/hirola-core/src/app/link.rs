@@ -0,0 +1,177 @@
+//! Navigation links that track the active route.
+use super::router::Router;
+use crate::{
+    dom::Dom,
+    generic_node::{DomType, GenericNode},
+    render::{Error, Render},
+};
+use futures_signals::signal::SignalExt;
+#[cfg(feature = "dom")]
+use wasm_bindgen::JsCast;
+
+/// A link to a route that is styled when the route is active.
+///
+/// `Link` renders an `<a>` pointing to `to`. While the current route matches, the link gets
+/// `active_class` and `aria-current="page"`. If `exact` is `false`, nested routes also match,
+/// eg. a link to `/docs` is active on `/docs/router`.
+///
+/// Plain clicks navigate using the [`Router`], while clicks with a modifier key or a mouse
+/// button other than the primary one are left to the browser. Under SSR a plain `<a>` is rendered.
+///
+/// # Example
+///
+/// ```no_run
+/// use hirola::prelude::*;
+///
+/// fn nav(app: &App<()>) -> Dom {
+///     let router = app.router().clone();
+///     html! {
+///         <nav>
+///             <Link to="/" router=router.clone() active_class="active" exact=true>"Home"</Link>
+///             <Link to="/docs" router=router active_class="active" exact=false>"Docs"</Link>
+///         </nav>
+///     }
+/// }
+/// ```
+pub struct Link<S: 'static, T: AsRef<str>> {
+    /// The route path to navigate to.
+    pub to: T,
+    /// The router used to navigate and to track the active route.
+    pub router: Router<S>,
+    /// The class added while the link is active.
+    pub active_class: &'static str,
+    /// Whether only the exact route path marks the link as active.
+    pub exact: bool,
+    /// The content of the link.
+    pub children: Dom,
+}
+
+/// Checks if `current` matches the link target `to`.
+fn is_active(current: &str, to: &str, exact: bool) -> bool {
+    if exact || to == "/" {
+        return current == to;
+    }
+    let to = to.trim_end_matches('/');
+    current == to || current.starts_with(&format!("{to}/"))
+}
+
+/// Adds or removes `active_class` and `aria-current`, keeping the other classes of the link.
+fn set_active(node: &DomType, active_class: &str, active: bool) {
+    let class = node.get_attribute("class").unwrap_or_default();
+    let mut classes: Vec<&str> = class
+        .split_whitespace()
+        .filter(|class| *class != active_class)
+        .collect();
+    if active {
+        if !active_class.is_empty() {
+            classes.push(active_class);
+        }
+        node.set_attribute("aria-current", "page");
+    } else {
+        node.remove_attribute("aria-current");
+    }
+    if classes.is_empty() {
+        node.remove_attribute("class");
+    } else {
+        node.set_attribute("class", &classes.join(" "));
+    }
+}
+
+/// Checks if a click event should be handled by the router instead of the browser.
+///
+/// Clicks with modifier keys or non primary buttons, clicks on links with a `target`, a `download`
+/// attribute, an external `href` or an in-page anchor such as `#install` are left to the browser.
+#[cfg(feature = "dom")]
+pub(crate) fn is_router_click(e: &web_sys::Event) -> bool {
+    if e.default_prevented() {
+        return false;
+    }
+    if let Some(e) = e.dyn_ref::<web_sys::MouseEvent>() {
+        if e.button() != 0 || e.meta_key() || e.ctrl_key() || e.shift_key() || e.alt_key() {
+            return false;
+        }
+    }
+    let element = match e
+        .current_target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+    {
+        Some(element) => element,
+        None => return true,
+    };
+    if matches!(element.get_attribute("target"), Some(target) if !target.is_empty() && target != "_self")
+    {
+        return false;
+    }
+    if element.has_attribute("download") {
+        return false;
+    }
+    match element.get_attribute("href") {
+        Some(href) => !is_external(&href) && !is_anchor(&href),
+        None => true,
+    }
+}
+
+/// Checks if `href` points outside the app, eg. `https://example.com` or `mailto:me@example.com`.
+#[cfg(feature = "dom")]
+fn is_external(href: &str) -> bool {
+    href.starts_with("//")
+        || href
+            .split_once(':')
+            .map(|(scheme, _)| !scheme.contains('/'))
+            .unwrap_or(false)
+}
+
+/// Checks if `href` only points to an element of the current page, eg. `#install`.
+///
+/// Hash routes such as `#/about` are not anchors.
+#[cfg(feature = "dom")]
+fn is_anchor(href: &str) -> bool {
+    href.starts_with('#') && !href.starts_with("#/")
+}
+
+impl<S: Clone + 'static, T: AsRef<str> + 'static> Render for Link<S, T> {
+    fn render_into(self: Box<Self>, parent: &Dom) -> Result<(), Error> {
+        let Link {
+            to,
+            router,
+            active_class,
+            exact,
+            children,
+        } = *self;
+        let to = to.as_ref().to_string();
+        let dom = Dom::element("a");
+        dom.attribute("href", &router.history().href(&to));
+        set_active(
+            dom.node(),
+            active_class,
+            is_active(&router.current(), &to, exact),
+        );
+        dom.append_child(children)?;
+
+        let node = dom.node().clone();
+        let target = to.clone();
+        dom.effect(
+            router
+                .signal()
+                .map(move |current| is_active(&current, &target, exact))
+                .dedupe()
+                .for_each(move |active| {
+                    set_active(&node, active_class, active);
+                    async {}
+                }),
+        );
+
+        #[cfg(feature = "dom")]
+        dom.event(
+            "click",
+            Box::new(move |e: web_sys::Event| {
+                if is_router_click(&e) {
+                    e.prevent_default();
+                    router.push(&to);
+                }
+            }),
+        );
+
+        parent.append_child(dom)
+    }
+}
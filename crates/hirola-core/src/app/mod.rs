@@ -1,13 +1,57 @@
+#[cfg(feature = "dom")]
+pub mod handle;
+pub mod history;
+pub mod link;
+pub mod plugin;
+#[cfg(feature = "ssr")]
+pub mod pool;
+pub mod response;
 pub mod router;
+#[cfg(feature = "tower")]
+pub mod service;
+#[cfg(feature = "serde")]
+pub mod state;
+pub mod static_site;
+#[cfg(feature = "dom")]
+pub use handle::AppHandle;
+use history::HistoryBackend;
+pub use link::Link;
+pub use plugin::Plugin;
+pub use response::Response;
 use router::Router;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::rc::Rc;
 
-use crate::{dom::Dom};
+use crate::dom::Dom;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct App<S: 'static> {
     router: Router<S>,
     state: S,
+    /// Extra data attached by plugins and modules, keyed by type.
+    data: HashMap<TypeId, Rc<dyn Any>>,
+    /// The plugins added with [`App::plugin`].
+    plugins: Vec<Rc<dyn Plugin<S>>>,
+    /// The response being built while rendering on the server.
+    response: Rc<RefCell<Response>>,
+    /// The URL the site is served from, used for absolute links such as sitemap entries.
+    site_url: Option<String>,
+}
+
+impl<S: Debug> Debug for App<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("App")
+            .field("router", &self.router)
+            .field("state", &self.state)
+            .field("data", &self.data.len())
+            .field("plugins", &self.plugins.len())
+            .field("response", &self.response)
+            .field("site_url", &self.site_url)
+            .finish()
+    }
 }
 
 /// The main application struct for the frontend app.
@@ -44,6 +88,10 @@ impl<S: Clone + 'static> App<S> {
         Self {
             state,
             router: Router::new(),
+            data: HashMap::new(),
+            plugins: Vec::new(),
+            response: Default::default(),
+            site_url: None,
         }
     }
 
@@ -56,6 +104,63 @@ impl<S: Clone + 'static> App<S> {
         &self.state
     }
 
+    /// Attach data of type `T` to the application, replacing any existing value of that type.
+    ///
+    /// This lets plugins and modules provide their own services without adding them to the
+    /// state type `S`. Clones of the app share the data.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// struct ApiClient {
+    ///     base_url: String,
+    /// }
+    ///
+    /// let mut app = App::new(());
+    /// app.insert_data(ApiClient { base_url: "/api".to_string() });
+    /// assert_eq!(app.data::<ApiClient>().unwrap().base_url, "/api");
+    /// ```
+    pub fn insert_data<T: 'static>(&mut self, data: T) {
+        self.data.insert(TypeId::of::<T>(), Rc::new(data));
+    }
+
+    /// Get a reference to the data of type `T` attached with [`App::insert_data`].
+    ///
+    /// # Returns
+    ///
+    /// `None` if no data of type `T` was attached.
+    pub fn data<T: 'static>(&self) -> Option<&T> {
+        self.data
+            .get(&TypeId::of::<T>())
+            .and_then(|data| data.downcast_ref())
+    }
+
+    /// Add a plugin to the application.
+    ///
+    /// The plugin is installed right away, see [`Plugin`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// struct Auth;
+    ///
+    /// impl<S: Clone + 'static> Plugin<S> for Auth {
+    ///     fn install(&self, app: &mut App<S>) {
+    ///         app.router()
+    ///             .before_navigate(|_, to| !to.starts_with("/admin"));
+    ///     }
+    /// }
+    ///
+    /// let mut app = App::new(());
+    /// app.plugin(Auth);
+    /// ```
+    pub fn plugin(&mut self, plugin: impl Plugin<S> + 'static) {
+        plugin.install(self);
+        self.plugins.push(Rc::new(plugin));
+    }
+
     /// Get a reference to the router associated with the application.
     ///
     /// # Returns
@@ -93,7 +198,81 @@ impl<S: Clone + 'static> App<S> {
     /// app.route("/about", about_page);
     /// ```
     pub fn route(&mut self, path: &str, page: fn(&Self) -> Dom) {
-        self.router.handler.insert(path.to_string(), page).unwrap();
+        self.router.insert(path, page);
+    }
+
+    /// Add a new route whose page is rendered with data from an async loader.
+    ///
+    /// The previous page stays visible while the loader runs, see
+    /// [`Router::is_navigating`](router::Router::is_navigating). Under SSR the loader is awaited
+    /// before rendering.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path for the new route, a string representing the route pattern.
+    /// * `loader` - A function that takes a reference to the `App<S>` and returns a future
+    ///   resolving to the page data.
+    /// * `page` - A function that takes a reference to the `App<S>` and the loaded data, and returns
+    ///   a `Dom` element.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// async fn fetch_posts() -> Vec<String> {
+    ///     vec!["Hello".to_string()]
+    /// }
+    ///
+    /// fn posts_page(_: &App<()>, posts: Vec<String>) -> Dom {
+    ///     html! { <h1>{format!("{} posts", posts.len())}</h1> }
+    /// }
+    ///
+    /// let mut app = App::new(());
+    /// app.route_with_loader("/posts", |_| fetch_posts(), posts_page);
+    /// ```
+    pub fn route_with_loader<T, L, F>(&mut self, path: &str, loader: L, page: fn(&Self, T) -> Dom)
+    where
+        T: 'static,
+        L: Fn(&Self) -> F + 'static,
+        F: std::future::Future<Output = T> + 'static,
+    {
+        self.router.insert_with_loader(path, loader, page);
+    }
+
+    /// Add a new route whose loader data is embedded in the server response.
+    ///
+    /// The data resolved while rendering with [`App::render_with_state`] is reused by the client's
+    /// first render instead of running the loader again, see [`App::from_embedded_state`].
+    ///
+    /// _This API requires the following crate features to be activated: `serde`_
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// async fn fetch_posts() -> Vec<String> {
+    ///     vec!["Hello".to_string()]
+    /// }
+    ///
+    /// fn posts_page(_: &App<()>, posts: Vec<String>) -> Dom {
+    ///     html! { <h1>{format!("{} posts", posts.len())}</h1> }
+    /// }
+    ///
+    /// let mut app = App::new(());
+    /// app.route_with_embedded_loader("/posts", |_| fetch_posts(), posts_page);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn route_with_embedded_loader<T, L, F>(
+        &mut self,
+        path: &str,
+        loader: L,
+        page: fn(&Self, T) -> Dom,
+    ) where
+        T: serde::Serialize + serde::de::DeserializeOwned + 'static,
+        L: Fn(&Self) -> F + 'static,
+        F: std::future::Future<Output = T> + 'static,
+    {
+        self.router.insert_with_embedded_loader(path, loader, page);
     }
 
     /// Set the not-found page for the application.
@@ -123,6 +302,99 @@ impl<S: Clone + 'static> App<S> {
     pub fn set_not_found(&mut self, page: fn(&Self) -> Dom) {
         self.router.set_not_found(page);
     }
+
+    /// Set the history backend used by the application's router.
+    ///
+    /// Use [`HashHistory`](history::HashHistory) for static file servers,
+    /// [`BrowserHistory`](history::BrowserHistory) with a `base` to serve the app under a sub path, or
+    /// [`MemoryHistory`](history::MemoryHistory) to drive routing without a browser.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The backend the router reads, pushes and listens to locations with.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    /// use hirola::prelude::history::MemoryHistory;
+    ///
+    /// let mut app = App::new(());
+    /// app.set_history(MemoryHistory::new("/"));
+    /// ```
+    pub fn set_history(&mut self, history: impl HistoryBackend + 'static) {
+        self.router.set_history(history);
+    }
+
+    /// Set the HTTP status code of the server response.
+    ///
+    /// Pages call this while rendering, eg. to answer `404` when a requested item does not exist.
+    /// It has no effect in the browser.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// fn post_page(app: &App<()>) -> Dom {
+    ///     app.set_status(410);
+    ///     html! { <h1>"This post was removed"</h1> }
+    /// }
+    /// ```
+    pub fn set_status(&self, status: u16) {
+        self.response.borrow_mut().status = status;
+    }
+
+    /// Add a header to the server response.
+    ///
+    /// It has no effect in the browser.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// fn home_page(app: &App<()>) -> Dom {
+    ///     app.insert_header("Cache-Control", "max-age=60");
+    ///     html! { <h1>"Home"</h1> }
+    /// }
+    /// ```
+    pub fn insert_header(&self, name: &str, value: &str) {
+        self.response
+            .borrow_mut()
+            .headers
+            .push((name.to_string(), value.to_string()));
+    }
+
+    /// Redirect to another route path.
+    ///
+    /// On the server, the response gets a `302 Found` status, unless a redirect status was already set,
+    /// and a `Location` header. In the browser, the router replaces the current route with `to`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// fn old_page(app: &App<()>) -> Dom {
+    ///     app.redirect("/new");
+    ///     html! { <></> }
+    /// }
+    /// ```
+    pub fn redirect(&self, to: &str) {
+        {
+            let mut response = self.response.borrow_mut();
+            if !response.is_redirect() {
+                response.status = 302;
+            }
+            response
+                .headers
+                .retain(|(key, _)| !key.eq_ignore_ascii_case("location"));
+            response
+                .headers
+                .push(("Location".to_string(), self.router.history().href(to)));
+        }
+        #[cfg(feature = "dom")]
+        if crate::generic_node::backend() == crate::generic_node::Backend::Dom {
+            self.router.replace(to);
+        }
+    }
 }
 
 #[cfg(feature = "dom")]
@@ -133,6 +405,9 @@ impl<S: Clone + 'static> App<S> {
     /// It mounts the application on the web page body, rendering the appropriate page based on the
     /// current route. The rendering process will be managed by the `Router` associated with the app.
     ///
+    /// The app stays mounted until the returned [`AppHandle`] is unmounted or dropped. Call
+    /// [`AppHandle::forget`] to keep it mounted for the lifetime of the page.
+    ///
     /// # Panics
     ///
     /// This method will panic if it fails to access the `window` or `document` objects from the
@@ -155,21 +430,13 @@ impl<S: Clone + 'static> App<S> {
     ///     // ... add routes and set up the app ...
     ///     
     ///     // Mount the app on the web page body and start rendering
-    ///     app.mount();
+    ///     app.mount().forget();
     /// }
     /// ```
-    pub fn mount(&self) {
+    pub fn mount(&self) -> AppHandle {
         let window = web_sys::window().unwrap();
         let document = window.document().unwrap();
-        let router = self.router.clone();
-        let dom = router.render(
-            &self,
-            &crate::generic_node::DomNode {
-                node: document.body().unwrap().into(),
-            },
-        );
-        // We leak the root node to avoid callbacks and futures being dropped
-        std::mem::forget(dom);
+        self.mount_to(&document.body().unwrap())
     }
 
     /// Mounts the application on a specified parent node and starts the rendering process.
@@ -203,19 +470,22 @@ impl<S: Clone + 'static> App<S> {
     ///         .unwrap();
     ///
     ///     // Mount the app on the specified parent node and start rendering
-    ///     app.mount_to(&parent_node);
+    ///     let handle = app.mount_to(&parent_node);
+    ///
+    ///     // Remove the app from the page
+    ///     handle.unmount();
     /// }
     /// ```
-    pub fn mount_to(&self, parent: &web_sys::Node) {
+    pub fn mount_to(&self, parent: &web_sys::Node) -> AppHandle {
         let router = self.router.clone();
         let dom = router.render(
             &self,
-            &crate::generic_node::DomNode {
+            &crate::generic_node::from_dom(crate::generic_node::DomNode {
                 node: parent.clone(),
-            },
+            }),
         );
-        // We leak the root node to avoid callbacks and futures being dropped
-        std::mem::forget(dom);
+        self.mount_plugins(&dom);
+        AppHandle::new(dom, parent)
     }
 
     /// Mounts the application on a specified parent node and starts the rendering process.
@@ -236,6 +506,7 @@ impl<S: Clone + 'static> App<S> {
     /// ```no_run
     /// fn main() {
     ///     use hirola::prelude::*;
+    ///     use hirola_core::generic_node::DomNode;
     ///     #[derive(Clone)]
     ///     struct AppState {
     ///         // ... fields and methods for your application state ...
@@ -255,9 +526,9 @@ impl<S: Clone + 'static> App<S> {
     ///     // In this example, we wrap the rendered content with a layout component
     ///     app.mount_with(&parent_node, |app| {
     ///         let router = app.router().clone();
-    ///         let inner = router.render(app, &DomType {
+    ///         let inner = router.render(app, &DomNode {
     ///             node: parent_node.clone().into()
-    ///         });
+    ///         }.into());
     ///         html! {
     ///             <main>
     ///                <nav>
@@ -271,14 +542,74 @@ impl<S: Clone + 'static> App<S> {
     ///                 </main>
     ///             </main>
     ///         }
-    ///     });
+    ///     })
+    ///     .forget();
     /// }
     /// ```
-    pub fn mount_with(&self, parent: &web_sys::Node, cb: impl Fn(&Self) -> Dom) {
+    pub fn mount_with(&self, parent: &web_sys::Node, cb: impl Fn(&Self) -> Dom) -> AppHandle {
         let res = cb(self);
-        parent.append_child(&res.node().inner_element()).unwrap();
-        // We leak the root node to avoid callbacks and futures being dropped
-        std::mem::forget(res);
+        let handle = AppHandle::appended(res, parent);
+        parent
+            .append_child(&crate::generic_node::as_dom(handle.root().node()).inner_element())
+            .unwrap();
+        self.mount_plugins(handle.root());
+        handle
+    }
+
+    /// Mounts the application on the web page body, replacing the markup rendered by the server.
+    ///
+    /// See [`App::hydrate_to`].
+    pub fn hydrate(&self) -> AppHandle {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        self.hydrate_to(&document.body().unwrap())
+    }
+
+    /// Mounts the application on a specified parent node, replacing the markup rendered by the
+    /// server.
+    ///
+    /// The children of `parent` are taken as the server's render of the app, eg. the body of
+    /// [`App::render_with_state`]. They are removed once the app is mounted, before the browser
+    /// paints again, so the page never shows both renders. The server nodes are not reused, so
+    /// anything the user changed before, such as the value of an input, is lost. Pages whose loaders
+    /// are still pending are empty until loaded, use
+    /// [embedded loaders](App::route_with_embedded_loader) to render the first page right away.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// let app = App::new(());
+    /// // ... add the same routes as the server ...
+    /// let parent = web_sys::window()
+    ///     .unwrap()
+    ///     .document()
+    ///     .unwrap()
+    ///     .get_element_by_id("app-container")
+    ///     .unwrap();
+    /// app.hydrate_to(&parent).forget();
+    /// ```
+    pub fn hydrate_to(&self, parent: &web_sys::Node) -> AppHandle {
+        let server = parent.child_nodes();
+        let server: Vec<web_sys::Node> = (0..server.length())
+            .filter_map(|index| server.item(index))
+            .collect();
+        let handle = self.mount_to(parent);
+        for node in server {
+            let _ = parent.remove_child(&node);
+        }
+        handle
+    }
+
+    /// Runs the plugins' mount hooks, and their unmount hooks once `dom` is discarded.
+    fn mount_plugins(&self, dom: &Dom) {
+        for plugin in &self.plugins {
+            plugin.mount(self);
+            let plugin = Rc::clone(plugin);
+            let app = self.clone();
+            dom.on_cleanup(move || plugin.unmount(&app));
+        }
     }
 }
 
@@ -318,13 +649,280 @@ impl<S: Clone + 'static> App<S> {
     /// }
     /// ```
     pub fn render_to_string(&self, path: &str) -> String {
-        use crate::generic_node::GenericNode;
-        let fragment = crate::generic_node::SsrNode::fragment();
-        let router = self.router().clone();
-        // Set the path
-        router.push(path);
-        // Render path to fragment
-        router.render(self, &fragment);
-        format!("{fragment}")
+        self.render_response(path).body
+    }
+
+    /// Renders the application to a string, awaiting the route's data loader if it has one.
+    ///
+    /// Use this instead of [`App::render_to_string`] when rendering inside an async runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path for the route to render, a string representing the route pattern.
+    ///
+    /// # Returns
+    ///
+    /// A string containing the HTML representation of the rendered content.
+    pub async fn render_to_string_async(&self, path: &str) -> String {
+        self.render_response_async(path).await.body
+    }
+
+    /// Renders the application to a [`Response`] based on the specified route path.
+    ///
+    /// Unlike [`App::render_to_string`], the response carries the status code and headers set while
+    /// rendering. Paths that match no route get a `404` status, and [`App::redirect`] sets a `3xx`
+    /// status with a `Location` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path for the route to render, a string representing the route pattern.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// let app = App::new(());
+    /// // ... add routes and set up the app ...
+    ///
+    /// let response = app.render_response("/missing");
+    /// assert_eq!(response.status, 404);
+    /// ```
+    pub fn render_response(&self, path: &str) -> Response {
+        let (mut response, tree) = self.render_tree(path);
+        response.body = tree.to_string();
+        response
+    }
+
+    /// Renders `path` to a [`Response`] without a body and the tree of the page, to be serialized
+    /// by the caller.
+    pub(crate) fn render_tree(&self, path: &str) -> (Response, crate::generic_node::SsrNode) {
+        let page = self.router.resolve(path);
+        let status = self.status_of(path);
+        let render = PageRender::start(self, path, status);
+        let dom = match page.render(&render.app) {
+            Some(dom) => dom,
+            // Only pages with a data loader need an executor
+            None => futures_executor::block_on(page.load(&render.app)),
+        };
+        render.finish(dom)
+    }
+
+    /// Renders the application to a [`Response`], awaiting the route's data loader if it has one.
+    ///
+    /// See [`App::render_response`].
+    pub async fn render_response_async(&self, path: &str) -> Response {
+        self.render_page(path, self.router.resolve(path), self.status_of(path))
+            .await
+    }
+
+    /// Renders the application to a complete HTML document using `shell`.
+    ///
+    /// The response body is the filled shell, see [`Shell`](crate::document::Shell).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::*;
+    /// use hirola_core::document::Shell;
+    ///
+    /// let app = App::new(());
+    /// // ... add routes and set up the app ...
+    ///
+    /// let shell = Shell::default().script("/pkg/app.js");
+    /// let response = app.render_document("/", &shell);
+    /// assert!(response.body.starts_with("<!DOCTYPE html>"));
+    /// ```
+    pub fn render_document(&self, path: &str, shell: &crate::document::Shell) -> Response {
+        let mut response = self.render_response(path);
+        response.body = shell.fill(&response.head, &response.body);
+        response
+    }
+
+    /// Renders the application to a complete HTML document, awaiting the route's data loader if it
+    /// has one.
+    ///
+    /// See [`App::render_document`].
+    pub async fn render_document_async(
+        &self,
+        path: &str,
+        shell: &crate::document::Shell,
+    ) -> Response {
+        let mut response = self.render_response_async(path).await;
+        response.body = shell.fill(&response.head, &response.body);
+        response
+    }
+
+    /// The status of a response for `path`, `404` if it matches no route.
+    fn status_of(&self, path: &str) -> u16 {
+        match self.router.handler.at(path) {
+            Ok(_) => 200,
+            Err(_) => 404,
+        }
+    }
+
+    /// Renders `page` for `path` to a [`Response`] starting with `status`.
+    async fn render_page(&self, path: &str, page: router::Page<S>, status: u16) -> Response {
+        let render = PageRender::start(self, path, status);
+        let dom = page.load(&render.app).await;
+        let (mut response, tree) = render.finish(dom);
+        response.body = tree.to_string();
+        response
+    }
+}
+
+/// A page being rendered on the server.
+#[cfg(feature = "ssr")]
+struct PageRender<S: 'static> {
+    /// The app with the response of this render.
+    app: App<S>,
+    head: crate::head::HeadCollector,
+    // Dropped last, so the page is rendered with the `Ssr` backend
+    _scope: crate::generic_node::SsrScope,
+}
+
+#[cfg(feature = "ssr")]
+impl<S: Clone + 'static> PageRender<S> {
+    /// Prepares `app` to render the page at `path`, starting with `status`.
+    fn start(app: &App<S>, path: &str, status: u16) -> Self {
+        let scope = crate::generic_node::SsrScope::enter();
+        // Each render builds its own response
+        let app = App {
+            response: Default::default(),
+            ..app.clone()
+        };
+        app.set_status(status);
+        app.router.set_location(path);
+        Self {
+            app,
+            // Collect the head entries set by the page
+            head: crate::head::HeadCollector::start(),
+            _scope: scope,
+        }
+    }
+
+    /// Mounts the rendered page, returning the response without a body and the tree of the page.
+    fn finish(self, page: Dom) -> (Response, crate::generic_node::SsrNode) {
+        use crate::generic_node::{from_ssr, GenericNode, SsrNode};
+        let fragment = SsrNode::fragment();
+        page.mount(&from_ssr(fragment.clone())).unwrap();
+        let mut response = self.app.response.take();
+        response.head = self.head.finish();
+        (response, fragment)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<S: Clone + serde::de::DeserializeOwned + 'static> App<S> {
+    /// Creates the application from state serialized by [`App::render_with_state`].
+    ///
+    /// The loader data embedded by the server is used by the first render of
+    /// [embedded loader](App::route_with_embedded_loader) routes.
+    ///
+    /// _This API requires the following crate features to be activated: `serde`_
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The content of the embedded state script tag.
+    pub fn from_state_json(json: &str) -> Result<Self, serde_json::Error> {
+        let embedded: state::EmbeddedState<S> = serde_json::from_str(json)?;
+        let mut app = Self::new(embedded.state);
+        app.insert_data(state::LoaderData::embedded(embedded.data));
+        Ok(app)
+    }
+
+    /// Creates the application from the state embedded in the page by [`App::render_with_state`].
+    ///
+    /// Routes still need to be added before mounting, so that the first render matches the
+    /// server's.
+    ///
+    /// _This API requires the following crate features to be activated: `dom`, `serde`_
+    ///
+    /// # Returns
+    ///
+    /// `None` if the page has no embedded state or it could not be deserialized.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    /// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+    /// struct AppState {
+    ///     user: String,
+    /// }
+    ///
+    /// let mut app = App::<AppState>::from_embedded_state().unwrap();
+    /// // ... add the same routes as the server ...
+    /// app.hydrate().forget();
+    /// ```
+    #[cfg(feature = "dom")]
+    pub fn from_embedded_state() -> Option<Self> {
+        let json = web_sys::window()?
+            .document()?
+            .get_element_by_id(state::STATE_ELEMENT_ID)?
+            .text_content()?;
+        Self::from_state_json(&json)
+            .map_err(|e| log::warn!("Could not restore the embedded state: {e}"))
+            .ok()
+    }
+}
+
+#[cfg(all(feature = "ssr", feature = "serde"))]
+impl<S: Clone + serde::Serialize + 'static> App<S> {
+    /// Renders the application to a [`Response`] embedding the app state for the client.
+    ///
+    /// The body ends with a `<script type="application/json">` tag holding the serialized state and
+    /// the data resolved by [embedded loaders](App::route_with_embedded_loader). The client restores
+    /// them with [`App::from_embedded_state`].
+    ///
+    /// _This API requires the following crate features to be activated: `ssr`, `serde`_
+    ///
+    /// # Panics
+    ///
+    /// If the state or the loader data cannot be serialized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::*;
+    /// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+    /// struct AppState {
+    ///     user: String,
+    /// }
+    ///
+    /// let app = App::new(AppState { user: "Jane".to_string() });
+    /// // ... add routes ...
+    /// let response = app.render_with_state("/");
+    /// ```
+    pub fn render_with_state(&self, path: &str) -> Response {
+        let (app, store) = self.recording();
+        let response = app.render_response(path);
+        self.embed_state(&store, response)
+    }
+
+    /// Renders the application to a [`Response`] embedding the app state, see
+    /// [`App::render_with_state`].
+    pub async fn render_with_state_async(&self, path: &str) -> Response {
+        let (app, store) = self.recording();
+        let response = app.render_response_async(path).await;
+        self.embed_state(&store, response)
+    }
+
+    /// Returns a clone of the app recording the data resolved by embedded loaders.
+    fn recording(&self) -> (Self, state::LoaderData) {
+        let store = state::LoaderData::recording();
+        let mut app = self.clone();
+        app.insert_data(store.clone());
+        (app, store)
+    }
+
+    /// Appends the state and the recorded loader data to the response body.
+    fn embed_state(&self, store: &state::LoaderData, mut response: Response) -> Response {
+        let embedded = state::EmbeddedState {
+            state: &self.state,
+            data: store.entries(),
+        };
+        let script = state::to_script(&embedded).expect("failed to serialize the app state");
+        response.body.push_str(&script);
+        response
     }
 }
@@ -0,0 +1,39 @@
+//! Plugins extending an [`App`] in one call.
+use super::App;
+
+/// A reusable extension of an [`App`], eg. analytics, authentication or translations.
+///
+/// [`Plugin::install`] runs once when the plugin is added with [`App::plugin`] and can register
+/// routes, data and navigation hooks. With the `dom` feature, [`Plugin::mount`] runs once the app is
+/// mounted and [`Plugin::unmount`] once it is discarded, which is where global listeners belong.
+///
+/// # Example
+///
+/// ```no_run
+/// use hirola::prelude::*;
+///
+/// struct Analytics {
+///     id: &'static str,
+/// }
+///
+/// impl<S: Clone + 'static> Plugin<S> for Analytics {
+///     fn install(&self, app: &mut App<S>) {
+///         let id = self.id;
+///         app.router()
+///             .after_navigate(move |_, to| log::info!("[{id}] page view: {to}"));
+///     }
+/// }
+///
+/// let mut app = App::new(());
+/// app.plugin(Analytics { id: "site" });
+/// ```
+pub trait Plugin<S: Clone + 'static> {
+    /// Extends the app, eg. by adding routes, data or navigation hooks.
+    fn install(&self, app: &mut App<S>);
+
+    /// Called once the app has been mounted.
+    fn mount(&self, _app: &App<S>) {}
+
+    /// Called once the mounted app is discarded.
+    fn unmount(&self, _app: &App<S>) {}
+}
@@ -0,0 +1,263 @@
+//! A pool of threads rendering an [`App`] on the server.
+//!
+//! [`App`] holds its routes and state in `Rc`s, so it cannot be shared between the threads of a
+//! multi-threaded runtime. A [`RenderPool`] starts a few render threads instead, each building its
+//! own app with a `Send` factory. Renders are queued from any thread and their results are
+//! awaited with a `Send` future, so the pool can be used from ordinary `axum` handlers:
+//!
+//! ```ignore
+//! async fn page(State(pool): State<RenderPool<AppState>>, uri: Uri) -> Html<String> {
+//!     Html(pool.render_to_string(uri.path()).await.unwrap())
+//! }
+//! ```
+//!
+//! _This API requires the following crate features to be activated: `ssr`, `app`_
+
+use super::{App, Response};
+use crate::document::Shell;
+use futures_channel::oneshot;
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll},
+    thread,
+};
+
+/// A job queued on [`Workers`], run with the value of its thread.
+type Job<T> = Box<dyn FnOnce(&T) + Send>;
+
+/// Threads running the jobs sent from any thread, each with a value built on the thread.
+pub(crate) struct Workers<T: 'static> {
+    jobs: mpsc::Sender<Job<T>>,
+    pub(crate) threads: usize,
+}
+
+impl<T: 'static> Workers<T> {
+    /// Starts `threads` threads, building their value with `init`.
+    ///
+    /// # Panics
+    ///
+    /// If `threads` is zero or a thread cannot be spawned.
+    pub(crate) fn new<F>(threads: usize, init: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        assert!(threads > 0, "a render pool needs at least one thread");
+        let (jobs, receiver) = mpsc::channel::<Job<T>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let init = Arc::new(init);
+        for index in 0..threads {
+            let receiver = Arc::clone(&receiver);
+            let init = Arc::clone(&init);
+            thread::Builder::new()
+                .name(format!("hirola-render-{index}"))
+                .spawn(move || {
+                    let value = init();
+                    loop {
+                        // The lock is released before running the job
+                        let job = receiver.lock().unwrap().recv();
+                        let Ok(job) = job else {
+                            // Every sender was dropped
+                            break;
+                        };
+                        // A panicking job only fails itself
+                        let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&value)));
+                    }
+                })
+                .expect("failed to spawn a render thread");
+        }
+        Self { jobs, threads }
+    }
+
+    /// Queues `job`, resolving to its result.
+    pub(crate) fn run<R, F>(&self, job: F) -> Rendering<R>
+    where
+        F: FnOnce(&T) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.spawn(move |value| {
+            let _ = sender.send(job(value));
+        });
+        Rendering { receiver }
+    }
+
+    /// Queues `job` without waiting for it.
+    pub(crate) fn spawn(&self, job: impl FnOnce(&T) + Send + 'static) {
+        // Without threads left, the job is dropped
+        let _ = self.jobs.send(Box::new(job));
+    }
+}
+
+impl<T: 'static> Clone for Workers<T> {
+    fn clone(&self) -> Self {
+        Self {
+            jobs: self.jobs.clone(),
+            threads: self.threads,
+        }
+    }
+}
+
+/// The number of threads used when none is given, one per available CPU.
+#[cfg(feature = "tower")]
+pub(crate) fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// A pool of threads, each rendering with its own [`App`].
+///
+/// The factory is called once on each thread, so the routes are built once per thread rather than
+/// for every request. Each render starts with a fresh [`Response`], so statuses and headers set by
+/// a page do not leak into the next render.
+///
+/// Cloning the pool is cheap, the clones queue their renders on the same threads. The threads stop
+/// once every clone is dropped.
+///
+/// # Example
+///
+/// ```
+/// use hirola::prelude::*;
+/// use hirola_core::app::pool::RenderPool;
+///
+/// fn home(_: &App<()>) -> Dom {
+///     html! { <h1>"Home"</h1> }
+/// }
+///
+/// let pool = RenderPool::new(2, || {
+///     let mut app = App::new(());
+///     app.route("/", home);
+///     app
+/// });
+///
+/// let html = futures_executor::block_on(pool.render_to_string("/")).unwrap();
+/// assert_eq!(html, "<h1>Home</h1>");
+/// ```
+pub struct RenderPool<S: 'static> {
+    workers: Workers<App<S>>,
+}
+
+impl<S: Clone + 'static> RenderPool<S> {
+    /// Starts `threads` render threads, building their app with `factory`.
+    ///
+    /// # Panics
+    ///
+    /// If `threads` is zero or a thread cannot be spawned.
+    pub fn new<F>(threads: usize, factory: F) -> Self
+    where
+        F: Fn() -> App<S> + Send + Sync + 'static,
+    {
+        Self {
+            workers: Workers::new(threads, factory),
+        }
+    }
+
+    /// Returns the number of render threads.
+    pub fn threads(&self) -> usize {
+        self.workers.threads
+    }
+
+    /// Runs `render` with the app of one of the threads.
+    ///
+    /// The app is shared by the renders of its thread, clone it to attach per-request data with
+    /// [`App::insert_data`]:
+    ///
+    /// ```
+    /// use hirola::prelude::*;
+    /// use hirola_core::app::pool::RenderPool;
+    ///
+    /// struct User(String);
+    ///
+    /// fn home(app: &App<()>) -> Dom {
+    ///     let name = app.data::<User>().unwrap().0.clone();
+    ///     html! { <h1>{name}</h1> }
+    /// }
+    ///
+    /// let pool = RenderPool::new(1, || {
+    ///     let mut app = App::new(());
+    ///     app.route("/", home);
+    ///     app
+    /// });
+    ///
+    /// let user = User("jane".to_string());
+    /// let html = pool.render(move |app| {
+    ///     let mut app = app.clone();
+    ///     app.insert_data(user);
+    ///     app.render_to_string("/")
+    /// });
+    /// assert_eq!(futures_executor::block_on(html).unwrap(), "<h1>jane</h1>");
+    /// ```
+    pub fn render<R, F>(&self, render: F) -> Rendering<R>
+    where
+        F: FnOnce(&App<S>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.workers.run(render)
+    }
+
+    /// Renders `path` to a string, see [`App::render_to_string`].
+    pub fn render_to_string(&self, path: &str) -> Rendering<String> {
+        let path = path.to_string();
+        self.render(move |app| app.render_to_string(&path))
+    }
+
+    /// Renders `path` to a [`Response`], see [`App::render_response`].
+    pub fn render_response(&self, path: &str) -> Rendering<Response> {
+        let path = path.to_string();
+        self.render(move |app| app.render_response(&path))
+    }
+
+    /// Renders `path` to a complete HTML document, see [`App::render_document`].
+    pub fn render_document(&self, path: &str, shell: Arc<Shell>) -> Rendering<Response> {
+        let path = path.to_string();
+        self.render(move |app| app.render_document(&path, &shell))
+    }
+}
+
+impl<S: 'static> Clone for RenderPool<S> {
+    fn clone(&self) -> Self {
+        Self {
+            workers: self.workers.clone(),
+        }
+    }
+}
+
+impl<S: 'static> fmt::Debug for RenderPool<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenderPool")
+            .field("threads", &self.workers.threads)
+            .finish()
+    }
+}
+
+/// A render queued on a [`RenderPool`], resolving to its result.
+#[derive(Debug)]
+#[must_use = "renders are only awaited if polled"]
+pub struct Rendering<R> {
+    receiver: oneshot::Receiver<R>,
+}
+
+impl<R> Future for Rendering<R> {
+    type Output = Result<R, RenderError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|result| result.map_err(|_| RenderError))
+    }
+}
+
+/// The error returned when a render panicked, or when no render thread is left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderError;
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the render thread failed")
+    }
+}
+
+impl Error for RenderError {}
@@ -0,0 +1,45 @@
+//! Responses produced when rendering an [`App`](super::App) on the server.
+
+/// The result of rendering a route on the server.
+///
+/// Pages can change the status and add headers while rendering using
+/// [`App::set_status`](super::App::set_status), [`App::insert_header`](super::App::insert_header)
+/// and [`App::redirect`](super::App::redirect).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code, `200` unless changed by a page, `404` for the not-found page.
+    pub status: u16,
+    /// Extra headers to send with the response.
+    pub headers: Vec<(String, String)>,
+    /// The head entries set by the page with [`Title`](crate::head::Title),
+    /// [`Meta`](crate::head::Meta) and [`HeadLink`](crate::head::HeadLink), as HTML.
+    pub head: String,
+    /// The rendered HTML.
+    pub body: String,
+}
+
+impl Response {
+    /// Returns the value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Checks if the response redirects to another location.
+    pub fn is_redirect(&self) -> bool {
+        (300..400).contains(&self.status)
+    }
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            head: String::new(),
+            body: String::new(),
+        }
+    }
+}
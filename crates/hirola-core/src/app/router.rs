@@ -1,9 +1,17 @@
+use super::history::{default_history, HistoryBackend};
+#[cfg(feature = "dom")]
+use super::history::{listen_window, Unlisten};
 use crate::{dom::Dom, prelude::*};
-use futures_signals::signal::{Mutable, MutableSignalCloned, SignalExt};
+use futures_signals::signal::{Mutable, MutableSignal, MutableSignalCloned, SignalExt};
+use futures_util::future::{select, Either};
+use futures_util::{FutureExt, StreamExt};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::fmt;
+use std::future::{ready, Future};
+use std::rc::Rc;
 #[cfg(feature = "dom")]
-use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use wasm_bindgen::JsCast;
 #[cfg(feature = "dom")]
 use web_sys::{Element, Event};
 
@@ -34,13 +42,28 @@ use web_sys::{Element, Event};
 /// let mut app = App::new(AppState { /* ... */ });
 /// app.route("/", home_page);
 /// app.route("/about", about_page);
-/// app.mount();
+/// app.mount().forget();
 /// ```
 #[derive(Clone)]
 pub struct Router<S: 'static = ()> {
     current: Mutable<String>,
-    /// The internal router used to map route paths to corresponding route handler functions.
-    pub(crate) handler: matchit::Router<fn(&App<S>) -> Dom>,
+    /// The backend used to read, push and listen to locations.
+    history: Rc<dyn HistoryBackend>,
+    /// The position of the current route in the history stack, used to undo blocked moves.
+    position: Rc<Cell<usize>>,
+    /// Whether a page is being loaded for a new route.
+    navigating: Mutable<bool>,
+    /// Callbacks run around route changes.
+    hooks: Rc<RefCell<NavigationHooks>>,
+    /// Saved scroll offsets and the scroll to apply after the next render.
+    #[cfg(feature = "dom")]
+    scroll: Rc<RefCell<ScrollState>>,
+    /// The internal router used to map route paths to corresponding pages.
+    pub(crate) handler: matchit::Router<Page<S>>,
+    /// The registered route patterns, in insertion order.
+    routes: Vec<String>,
+    /// The providers of concrete paths for routes with parameters, used when generating static sites.
+    static_paths: HashMap<String, StaticPaths<S>>,
     /// The function that will be executed when the requested route does not match any registered routes.
     pub(crate) not_found: Box<fn(&App<S>) -> Dom>,
 }
@@ -49,19 +72,176 @@ impl<S> fmt::Debug for Router<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Router")
             .field("current", &self.current)
-            .field(
-                "handler",
-                &format_args!("matchit::Router<fn(&App<S>) -> Dom>"),
-            )
+            .field("history", &self.history)
+            .field("navigating", &self.navigating)
+            .field("hooks", &self.hooks.borrow())
+            .field("handler", &format_args!("matchit::Router<Page<S>>"))
+            .field("routes", &self.routes)
+            .finish()
+    }
+}
+
+/// A callback run before navigating from a route path to another, returning `false` to cancel.
+pub type BeforeNavigate = Rc<dyn Fn(&str, &str) -> bool>;
+
+/// A callback run after the page for a new route path has been rendered.
+pub type AfterNavigate = Rc<dyn Fn(&str, &str)>;
+
+/// A callback run before leaving the current page, returning `false` to stay on it.
+pub type OnLeave = Rc<dyn Fn() -> bool>;
+
+#[derive(Default)]
+struct NavigationHooks {
+    before: Vec<BeforeNavigate>,
+    after: Vec<AfterNavigate>,
+    leave: Option<OnLeave>,
+}
+
+impl fmt::Debug for NavigationHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NavigationHooks")
+            .field("before", &self.before.len())
+            .field("after", &self.after.len())
+            .field("leave", &self.leave.is_some())
             .finish()
     }
 }
+
+/// Where to scroll once the page for a new route is rendered.
+#[cfg(feature = "dom")]
+#[derive(Debug)]
+enum Scroll {
+    /// The top of the page, after a new navigation.
+    Top,
+    /// The offset saved when the route was left, after going back or forward in history.
+    Restore,
+    /// The element with the given id, for paths with a fragment, eg. `/docs#install`.
+    Fragment(String),
+}
+
+#[cfg(feature = "dom")]
+#[derive(Debug, Default)]
+struct ScrollState {
+    positions: HashMap<String, (f64, f64)>,
+    pending: Option<Scroll>,
+}
+
+/// Checks if pages are rendered into the browser, rather than on the server with both the `dom` and
+/// `ssr` features activated.
+#[cfg(feature = "dom")]
+fn in_browser() -> bool {
+    crate::generic_node::backend() == crate::generic_node::Backend::Dom
+}
+
+/// Splits a path into the route path and the fragment, eg. `/docs#install` into `/docs` and `install`.
+fn split_fragment(path: &str) -> (&str, Option<&str>) {
+    match path.split_once('#') {
+        Some((route, fragment)) => (route, Some(fragment)),
+        None => (path, None),
+    }
+}
+
+/// Empty text nodes around the page rendered by a [`Router`], so that other children of its parent
+/// are left in place.
+struct PageBounds {
+    start: DomType,
+    end: DomType,
+}
+
+impl PageBounds {
+    /// Appends the bounds to `parent`.
+    fn append(parent: &DomType) -> Self {
+        let bounds = Self {
+            start: DomType::text_node(""),
+            end: DomType::text_node(""),
+        };
+        parent.append_child(&bounds.start);
+        parent.append_child(&bounds.end);
+        bounds
+    }
+
+    /// Removes the nodes of the current page.
+    fn clear(&self, parent: &DomType) {
+        while let Some(node) = self.start.next_sibling() {
+            if node == self.end {
+                break;
+            }
+            parent.remove_child(&node);
+        }
+    }
+
+    /// Puts the nodes of `page` in place of the current page.
+    fn replace(&self, parent: &DomType, page: &Dom) {
+        self.clear(parent);
+        parent.insert_child_before(page.node(), Some(&self.end));
+    }
+
+    /// Removes the bounds along with the current page.
+    fn remove(&self, parent: &DomType) {
+        self.clear(parent);
+        parent.remove_child(&self.start);
+        parent.remove_child(&self.end);
+    }
+}
+
+/// A function listing the concrete paths of a route with parameters, eg. `/posts/1` for `/posts/:id`.
+pub type StaticPaths<S> = Rc<dyn Fn(&App<S>) -> Vec<String>>;
+
+/// A function rendering a page once its data has loaded.
+pub type LoadPage<S> = Rc<dyn Fn(&App<S>) -> BoxedLocal<Dom>>;
+
+/// A page rendered by the [`Router`] for a route.
+pub enum Page<S: 'static> {
+    /// A page rendered synchronously from the app.
+    Static(fn(&App<S>) -> Dom),
+    /// A page rendered once its data loader has resolved.
+    Loaded(LoadPage<S>),
+}
+
+impl<S> Clone for Page<S> {
+    fn clone(&self) -> Self {
+        match self {
+            Page::Static(page) => Page::Static(*page),
+            Page::Loaded(page) => Page::Loaded(Rc::clone(page)),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Page<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Page::Static(_) => f.write_str("Page::Static"),
+            Page::Loaded(_) => f.write_str("Page::Loaded"),
+        }
+    }
+}
+
+impl<S: Clone + 'static> Page<S> {
+    /// Renders the page synchronously.
+    ///
+    /// Returns `None` if the page needs to load data first, see [`Page::load`].
+    pub fn render(&self, app: &App<S>) -> Option<Dom> {
+        match self {
+            Page::Static(page) => Some(page(app)),
+            Page::Loaded(_) => None,
+        }
+    }
+
+    /// Loads the page data, if any, and renders the page.
+    pub fn load(&self, app: &App<S>) -> BoxedLocal<Dom> {
+        match self {
+            Page::Static(page) => Box::pin(ready(page(app))),
+            Page::Loaded(page) => page(app),
+        }
+    }
+}
 impl<S: Clone + 'static> Router<S> {
     /// Creates a new instance of the Router with default settings.
     ///
     /// The `Router` manages the routing functionality for the frontend application. This method
     /// creates a new instance of the `Router` with an empty route handler and a default not-found
-    /// page handler.
+    /// page handler. Locations are managed by a [`BrowserHistory`](super::history::BrowserHistory)
+    /// when the `dom` feature is enabled, or a [`MemoryHistory`](super::history::MemoryHistory) otherwise.
     ///
     /// # Returns
     ///
@@ -75,19 +255,63 @@ impl<S: Clone + 'static> Router<S> {
     /// let router = Router::<()>::new();
     /// ```
     pub fn new() -> Self {
-        #[allow(unused_mut)]
-        let mut path = String::from("/");
-        #[cfg(feature = "dom")]
-        if let Some(window) = web_sys::window() {
-            path = window.location().pathname().unwrap_or("/".to_string());
-        }
+        let history = default_history();
         Router {
-            current: Mutable::new(path),
+            current: Mutable::new(history.location()),
+            position: Rc::new(Cell::new(history.index())),
+            history,
+            navigating: Mutable::new(false),
+            hooks: Default::default(),
+            #[cfg(feature = "dom")]
+            scroll: Default::default(),
             handler: Default::default(),
             not_found: Box::new(|_| Dom::text("Not Found")),
+            routes: Vec::new(),
+            static_paths: HashMap::new(),
         }
     }
 
+    /// Replaces the history backend used by the router.
+    ///
+    /// The current route is reset to the location reported by the new backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The backend to read, push and listen to locations with.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// use hirola::prelude::history::MemoryHistory;
+    /// let mut router = Router::<()>::new();
+    /// router.set_history(MemoryHistory::new("/about"));
+    /// ```
+    pub fn set_history(&mut self, history: impl HistoryBackend + 'static) {
+        self.current.set(history.location());
+        self.position.set(history.index());
+        self.history = Rc::new(history);
+    }
+
+    /// Retrieves the history backend used by the router.
+    pub fn history(&self) -> &Rc<dyn HistoryBackend> {
+        &self.history
+    }
+
+    /// Retrieves the current route path.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// let router = Router::<()>::new();
+    /// router.push("/about");
+    /// assert_eq!(router.current(), "/about");
+    /// ```
+    pub fn current(&self) -> String {
+        self.current.get_cloned()
+    }
+
     /// Retrieves the current parameters from the current route.
     ///
     /// This method returns a HashMap containing the parameters parsed from the current route
@@ -126,6 +350,9 @@ impl<S: Clone + 'static> Router<S> {
     /// This method updates the current route to the provided `path`. It will trigger the
     /// rendering process for the new route and update the application's UI accordingly.
     ///
+    /// The page is scrolled to the top, or to the element matching the fragment of `path` if any,
+    /// eg. `/docs#install`.
+    ///
     /// # Arguments
     ///
     /// * `path` - The path for the route to navigate to, a string representing the route pattern.
@@ -136,25 +363,165 @@ impl<S: Clone + 'static> Router<S> {
     /// use hirola::prelude::router::Router;
     /// let router = Router::<()>::new();
     /// router.push("/about");
+    /// ```
     pub fn push(&self, path: &str) {
+        self.navigate(path, false);
+    }
+
+    /// Navigates to the specified route path, replacing the current history entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path for the route to navigate to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// let router = Router::<()>::new();
+    /// router.replace("/login");
+    /// ```
+    pub fn replace(&self, path: &str) {
+        self.navigate(path, true);
+    }
+
+    fn navigate(&self, path: &str, replace: bool) {
+        let (route, fragment) = split_fragment(path);
+        if !self.can_navigate(route) {
+            return;
+        }
         #[cfg(feature = "dom")]
-        let window = web_sys::window().unwrap();
-        #[cfg(feature = "dom")]
-        window
-            .history()
-            .unwrap()
-            .push_state_with_url(&JsValue::default(), "", Some(&path))
-            .unwrap();
+        {
+            self.save_scroll();
+            self.scroll.borrow_mut().pending = Some(match fragment {
+                Some(id) => Scroll::Fragment(id.to_string()),
+                None => Scroll::Top,
+            });
+        }
+        if replace {
+            self.history.replace(path);
+        } else {
+            self.history.push(path);
+        }
+        self.position.set(self.history.index());
+        if fragment.is_some() && route == self.current.get_cloned() {
+            // Same page, only scroll to the fragment
+            #[cfg(feature = "dom")]
+            self.apply_scroll(route);
+            return;
+        }
+        self.current.set(route.to_owned());
+    }
+
+    /// Registers a callback run before each navigation with the current and the new route paths.
+    ///
+    /// Returning `false` cancels the navigation. Callbacks run in the order they were registered,
+    /// after the current page's [`Router::on_leave`] guard.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// let router = Router::<()>::new();
+    /// router.before_navigate(|_from, to| !to.starts_with("/admin"));
+    /// ```
+    pub fn before_navigate(&self, callback: impl Fn(&str, &str) -> bool + 'static) {
+        self.hooks.borrow_mut().before.push(Rc::new(callback));
+    }
+
+    /// Registers a callback run with the previous and the new route paths once the new page
+    /// has been rendered.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// let router = Router::<()>::new();
+    /// router.after_navigate(|from, to| log::info!("Navigated from {from} to {to}"));
+    /// ```
+    pub fn after_navigate(&self, callback: impl Fn(&str, &str) + 'static) {
+        self.hooks.borrow_mut().after.push(Rc::new(callback));
+    }
+
+    /// Registers a guard for the current page, run before navigating away from it.
+    ///
+    /// Returning `false` keeps the user on the page, eg. when a form has unsaved changes. The guard
+    /// is removed once navigation succeeds, so pages register it while rendering. Only one guard is
+    /// kept; registering another replaces it.
+    ///
+    /// With the `dom` feature, the guard also runs when the user closes or reloads the page. If it
+    /// returns `false` the browser asks for confirmation. Browsers do not allow dialogs during
+    /// unload, so the guard should only check the page state there.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// fn editor_page(app: &App<()>) -> Dom {
+    ///     let dirty = Mutable::new(false);
+    ///     let unsaved = dirty.clone();
+    ///     app.router().on_leave(move || !unsaved.get());
+    ///     // ... set `dirty` when the form changes ...
+    ///     html! {
+    ///         <form />
+    ///     }
+    /// }
+    /// ```
+    pub fn on_leave(&self, guard: impl Fn() -> bool + 'static) {
+        self.hooks.borrow_mut().leave = Some(Rc::new(guard));
+    }
+
+    /// Runs the leave guard and the before navigate callbacks for a navigation to `to`.
+    ///
+    /// Clears the leave guard if the navigation is allowed.
+    fn can_navigate(&self, to: &str) -> bool {
+        let from = self.current.get_cloned();
+        if from == to {
+            return true;
+        }
+        let (leave, before) = {
+            let hooks = self.hooks.borrow();
+            (hooks.leave.clone(), hooks.before.clone())
+        };
+        if leave.map(|guard| !guard()).unwrap_or(false) {
+            log::debug!("Navigation to {to} blocked by the current page");
+            return false;
+        }
+        if !before.iter().all(|callback| callback(&from, to)) {
+            log::debug!("Navigation to {to} cancelled");
+            return false;
+        }
+        self.hooks.borrow_mut().leave = None;
+        true
+    }
+
+    /// Sets the current location without running any navigation hooks.
+    #[cfg(feature = "ssr")]
+    pub(crate) fn set_location(&self, path: &str) {
+        self.hooks.borrow_mut().leave = None;
+        self.history.replace(path);
         self.current.set(path.to_owned());
     }
 
+    /// Checks whether the current page allows the browser to unload it.
+    #[cfg(feature = "dom")]
+    fn can_unload(&self) -> bool {
+        let leave = self.hooks.borrow().leave.clone();
+        leave.map(|guard| guard()).unwrap_or(true)
+    }
+
     /// Generates a link handler function that can be used to navigate to a specific route.
     ///
     /// This method returns a boxed closure that takes a reference to a DOM element (`Dom`) and
-    /// updates the current route to the specified path when triggered. It can be used to create
+    /// updates the current route to the element's `href` when clicked. It can be used to create
     /// link handlers for HTML elements, such as anchors (`<a>`), buttons, or custom elements,
     /// allowing users to navigate to different routes within the frontend application.
     ///
+    /// Clicks with a modifier key or a non primary mouse button, and clicks on elements with a
+    /// `target`, a `download` attribute or an external `href` are left to the browser.
+    /// For links that track the active route, see [`Link`](super::link::Link).
+    ///
     /// # Returns
     ///
     /// A boxed closure that can be attached as a mixin for a DOM element.
@@ -177,6 +544,9 @@ impl<S: Clone + 'static> Router<S> {
             let router = router.clone();
             #[cfg(feature = "dom")]
             let handle_click = Box::new(move |e: Event| {
+                if !super::link::is_router_click(&e) {
+                    return;
+                }
                 e.prevent_default();
                 let element = e.current_target().unwrap().dyn_into::<Element>().unwrap();
                 let href = element.get_attribute("href").unwrap();
@@ -211,6 +581,40 @@ impl<S: Clone + 'static> Router<S> {
         self.current.signal_cloned()
     }
 
+    /// Retrieves a signal that is `true` while the page for a new route is loading.
+    ///
+    /// While a route's loader runs, the previous page stays visible. This signal can be used
+    /// to show a progress indicator in the meantime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// use hirola::prelude::*;
+    /// let router = Router::<()>::new();
+    /// let progress = Switch {
+    ///     signal: router.is_navigating(),
+    ///     renderer: |loading| {
+    ///         if loading {
+    ///             html! { <span>"Loading..."</span> }
+    ///         } else {
+    ///             html! { <></> }
+    ///         }
+    ///     },
+    /// };
+    /// ```
+    pub fn is_navigating(&self) -> MutableSignal<bool> {
+        self.navigating.signal()
+    }
+
+    /// Resolves the page for `path`, falling back to the not-found page.
+    pub(crate) fn resolve(&self, path: &str) -> Page<S> {
+        match self.handler.at(path) {
+            Ok(matched) => matched.value.clone(),
+            Err(_) => Page::Static(*self.not_found),
+        }
+    }
+
     /// Renders the appropriate content for the current route and appends it to the specified parent.
     ///
     /// This method is used internally to render the content associated with the current route and
@@ -253,109 +657,206 @@ impl<S: Clone + 'static> Router<S> {
     /// router.render(&app, &DomType::fragment());
     /// ```
     pub fn render(self, app: &App<S>, parent: &DomType) -> Dom {
-        let router = &self.handler;
-        #[cfg(feature = "dom")]
-        let current = self.current.clone();
-        #[cfg(feature = "dom")]
-        //Hash routing forward in history and URL rewrite
-        let handle_hash = Closure::wrap(Box::new(move |_evt: web_sys::Event| {
-            let l: String = web_sys::window()
-                .unwrap()
-                .location()
-                .hash()
-                .unwrap()
-                .chars()
-                .skip(1)
-                .collect();
-
-            log::debug!("hash handle : {l}");
-
-            let h = web_sys::window().unwrap().history().unwrap();
-            h.replace_state_with_url(&JsValue::NULL, "", Some(l.as_str()))
-                .unwrap();
-
-            current.set(l.to_string());
-        }) as Box<dyn Fn(_)>);
-        #[cfg(feature = "dom")]
-        web_sys::window()
-            .unwrap()
-            .set_onhashchange(Some(handle_hash.as_ref().unchecked_ref()));
-        #[cfg(feature = "dom")]
-        handle_hash.forget();
-
+        let router = self.clone();
+        let unlisten = self.history.listen(Rc::new(move |path: String| {
+            let route = split_fragment(&path).0;
+            if route == router.current.get_cloned() {
+                // Fragment changes on the same page are scrolled by the browser
+                return;
+            }
+            if router.can_navigate(route) {
+                #[cfg(feature = "dom")]
+                {
+                    router.save_scroll();
+                    router.scroll.borrow_mut().pending = Some(Scroll::Restore);
+                }
+                router.position.set(router.history.index());
+                router.current.set(route.to_owned());
+            } else {
+                // The location already changed, so move back to the current one
+                let delta = router.position.get() as isize - router.history.index() as isize;
+                if delta != 0 {
+                    router.history.go(delta);
+                } else {
+                    router.history.replace(&router.current.get_cloned());
+                }
+            }
+        }));
         #[cfg(feature = "dom")]
-        let current = self.current.clone();
-        //Routing for navigating in history and escaping hash routes
+        let unlisten_unload = self.listen_unload();
         #[cfg(feature = "dom")]
-        let handle_pop = Closure::wrap(Box::new(move |_evt: web_sys::Event| {
-            let path_name = web_sys::window().unwrap().location().pathname().unwrap();
-
-            if web_sys::window()
+        if in_browser() {
+            // The router restores scroll offsets itself once pages are rendered
+            let _ = web_sys::window()
                 .unwrap()
-                .location()
-                .hash()
+                .history()
                 .unwrap()
-                .chars()
-                .count()
-                > 0
-            {
-                log::debug!("hash detected");
-                return ();
-            }
-            current.set(path_name.to_string());
-            log::debug!("pop handle : {path_name}");
-        }) as Box<dyn Fn(_)>);
-
-        #[cfg(feature = "dom")]
-        web_sys::window()
-            .unwrap()
-            .set_onpopstate(Some(handle_pop.as_ref().unchecked_ref()));
-
-        #[cfg(feature = "dom")]
-        handle_pop.forget();
+                .set_scroll_restoration(web_sys::ScrollRestoration::Manual);
+        }
         let route = &self.current.clone();
 
         let path = route.get_cloned();
-        let match_result = router.at(&path);
-        let page_fn = match match_result {
-            Ok(v) => v.value,
-            Err(_) => &self.not_found,
+        // Pages whose loaders are still pending are rendered by the route effect below
+        let mut initial = self.resolve(&path).load(app);
+        let dom = Dom::new_from_node(parent);
+        let bounds = Rc::new(PageBounds::append(parent));
+        // The mounted page, kept alive until the next page replaces it
+        let page: Rc<RefCell<Option<Dom>>> = Default::default();
+        let pending = match (&mut initial).now_or_never() {
+            Some(builder) => {
+                let dom = builder.mount(&DomType::fragment()).unwrap();
+                bounds.replace(parent, &dom);
+                page.replace(Some(dom));
+                None
+            }
+            None => Some(initial),
         };
+        // Listeners and the page are removed once the router is discarded
+        dom.on_cleanup(unlisten);
+        #[cfg(feature = "dom")]
+        dom.on_cleanup(unlisten_unload);
+        let mounted = Rc::clone(&page);
+        let node = parent.clone();
+        let mounted_bounds = Rc::clone(&bounds);
+        dom.on_cleanup(move || {
+            drop(mounted.take());
+            mounted_bounds.remove(&node);
+        });
 
-        let builder = page_fn(&app);
-        let dom = builder.mount(&parent).unwrap();
-
-        let router = router.clone();
+        let router = self.clone();
         let app = app.clone();
         let node = parent.clone();
-        let not_found = self.not_found.clone();
-        let wait_for_next_route = route
-            .signal_cloned()
-            .map(move |route_match| {
-                let match_result = router.at(&route_match);
-                let page_fn = match match_result {
-                    Ok(v) => v.value,
-                    Err(_) => &not_found,
-                };
-
-                let builder = page_fn(&app);
+        let mut routes = route.signal_cloned().to_stream();
+        let wait_for_next_route = async move {
+            let previous = RefCell::new(path.clone());
+            let show = |route_match: String, builder: Dom, initial: bool| {
                 let dom = builder.mount(&DomType::fragment()).unwrap();
-                node.replace_children_with(&dom.node());
-                #[cfg(feature = "dom")]
-                let window = web_sys::window().unwrap();
-                #[cfg(feature = "dom")]
-                window
-                    .history()
-                    .unwrap()
-                    .push_state_with_url(&JsValue::default(), "", Some(&route_match))
-                    .unwrap();
-                log::debug!("Router received new path: {route_match}");
-            })
-            .to_future();
+                bounds.replace(&node, &dom);
+                page.replace(Some(dom));
+                log::debug!("Router rendered path: {route_match}");
+                if !initial {
+                    #[cfg(feature = "dom")]
+                    router.apply_scroll(&route_match);
+                    router.navigated(&previous, route_match);
+                }
+            };
+            // The page being loaded, dropped without being mounted if another route is navigated
+            // to first. The current page is kept until then.
+            let mut loading = pending.map(|load| (path.clone(), load, true));
+            router.navigating.set_neq(loading.is_some());
+            let mut rendered = Some(path);
+            loop {
+                let next = match loading.as_mut() {
+                    Some((_, load, _)) => match select(routes.next(), load).await {
+                        Either::Left((next, _)) => next,
+                        Either::Right((builder, _)) => {
+                            let (route_match, _, initial) = loading.take().unwrap();
+                            router.navigating.set_neq(false);
+                            show(route_match, builder, initial);
+                            continue;
+                        }
+                    },
+                    None => routes.next().await,
+                };
+                let Some(route_match) = next else {
+                    break;
+                };
+                // The first route is already rendered, or loading
+                if rendered.take().as_ref() == Some(&route_match) {
+                    continue;
+                }
+                let mut load = router.resolve(&route_match).load(&app);
+                match (&mut load).now_or_never() {
+                    Some(builder) => {
+                        loading = None;
+                        router.navigating.set_neq(false);
+                        show(route_match, builder, false);
+                    }
+                    None => {
+                        loading = Some((route_match, load, false));
+                        router.navigating.set_neq(true);
+                    }
+                }
+            }
+        };
         dom.effect(wait_for_next_route);
         dom
     }
 
+    /// Runs the after navigate callbacks and records `to` as the previous route path.
+    fn navigated(&self, previous: &RefCell<String>, to: String) {
+        let from = previous.replace(to.clone());
+        let after = self.hooks.borrow().after.clone();
+        for callback in after {
+            callback(&from, &to);
+        }
+    }
+
+    /// Saves the scroll offset of the current route.
+    #[cfg(feature = "dom")]
+    fn save_scroll(&self) {
+        if !in_browser() {
+            return;
+        }
+        let window = web_sys::window().unwrap();
+        let offset = (
+            window.scroll_x().unwrap_or_default(),
+            window.scroll_y().unwrap_or_default(),
+        );
+        self.scroll
+            .borrow_mut()
+            .positions
+            .insert(self.current.get_cloned(), offset);
+    }
+
+    /// Applies the pending scroll once the page for `route` has been rendered.
+    #[cfg(feature = "dom")]
+    fn apply_scroll(&self, route: &str) {
+        if !in_browser() {
+            return;
+        }
+        let window = web_sys::window().unwrap();
+        let (x, y) = match self.scroll.borrow_mut().pending.take() {
+            None => return,
+            Some(Scroll::Top) => (0.0, 0.0),
+            Some(Scroll::Restore) => self
+                .scroll
+                .borrow()
+                .positions
+                .get(route)
+                .copied()
+                .unwrap_or_default(),
+            Some(Scroll::Fragment(id)) => {
+                let element = window
+                    .document()
+                    .and_then(|document| document.get_element_by_id(&id));
+                if let Some(element) = element {
+                    element.scroll_into_view();
+                    return;
+                }
+                (0.0, 0.0)
+            }
+        };
+        window.scroll_to_with_x_and_y(x, y);
+    }
+
+    /// Asks the browser to confirm closing or reloading the page when the leave guard blocks it.
+    #[cfg(feature = "dom")]
+    fn listen_unload(&self) -> Unlisten {
+        if !in_browser() {
+            return Box::new(|| {});
+        }
+        let router = self.clone();
+        listen_window("beforeunload", move |e: Event| {
+            if !router.can_unload() {
+                e.prevent_default();
+                if let Some(e) = e.dyn_ref::<web_sys::BeforeUnloadEvent>() {
+                    e.set_return_value("");
+                }
+            }
+        })
+    }
+
     /// Inserts a new route and its corresponding page rendering function into the router.
     ///
     /// This method registers a new route pattern and its associated page rendering function in the router.
@@ -394,7 +895,166 @@ impl<S: Clone + 'static> Router<S> {
     /// router.insert("/", home_page);
     /// ```
     pub fn insert(&mut self, path: &str, page: fn(&App<S>) -> Dom) {
+        self.insert_page(path, Page::Static(page));
+    }
+
+    /// Inserts a new route whose page is rendered with data from an async loader.
+    ///
+    /// When navigating to `path`, `loader` is called and the current page stays visible until
+    /// its future resolves. The result is then passed to `page`. Use [`Router::is_navigating`]
+    /// to show progress in the meantime. Under SSR, the loader is awaited before rendering.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A string representing the route pattern to match.
+    /// * `loader` - A function that takes a reference to the `App<S>` instance and returns a future
+    ///   resolving to the page data.
+    /// * `page` - A function that takes a reference to the `App<S>` instance and the loaded data,
+    ///   and returns the rendered DOM content (`Dom`).
+    ///
+    /// # Panics
+    ///
+    /// If the insertion into the router fails, this method will panic.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// use hirola::prelude::*;
+    ///
+    /// async fn fetch_user(id: String) -> String {
+    ///     format!("User {id}")
+    /// }
+    ///
+    /// fn user_page(_: &App<()>, name: String) -> Dom {
+    ///     html! {
+    ///         <h1>{name}</h1>
+    ///     }
+    /// }
+    ///
+    /// let mut router = Router::<()>::new();
+    /// router.insert_with_loader(
+    ///     "/users/:id",
+    ///     |app: &App<()>| {
+    ///         let id = app.router().current_params()["id"].clone();
+    ///         fetch_user(id)
+    ///     },
+    ///     user_page,
+    /// );
+    /// ```
+    pub fn insert_with_loader<T, L, F>(
+        &mut self,
+        path: &str,
+        loader: L,
+        page: fn(&App<S>, T) -> Dom,
+    ) where
+        T: 'static,
+        L: Fn(&App<S>) -> F + 'static,
+        F: Future<Output = T> + 'static,
+    {
+        let load = move |app: &App<S>| -> BoxedLocal<Dom> {
+            let data = loader(app);
+            let app = app.clone();
+            Box::pin(async move { page(&app, data.await) })
+        };
+        self.insert_page(path, Page::Loaded(Rc::new(load)));
+    }
+
+    /// Inserts a new route whose loader data is embedded in the server response.
+    ///
+    /// Works like [`Router::insert_with_loader`], but the data resolved on the server is embedded by
+    /// [`App::render_with_state`] and used by the client's first render, see
+    /// [`App::from_embedded_state`]. The loader only runs on the client for later navigations.
+    ///
+    /// _This API requires the following crate features to be activated: `serde`_
+    #[cfg(feature = "serde")]
+    pub fn insert_with_embedded_loader<T, L, F>(
+        &mut self,
+        path: &str,
+        loader: L,
+        page: fn(&App<S>, T) -> Dom,
+    ) where
+        T: serde::Serialize + serde::de::DeserializeOwned + 'static,
+        L: Fn(&App<S>) -> F + 'static,
+        F: Future<Output = T> + 'static,
+    {
+        use super::state::LoaderData;
+        let load = move |app: &App<S>| -> BoxedLocal<Dom> {
+            let key = app.router().current();
+            let store = app.data::<LoaderData>().cloned();
+            if let Some(data) = store.as_ref().and_then(|store| store.take(&key)) {
+                return Box::pin(ready(page(app, data)));
+            }
+            let data = loader(app);
+            let app = app.clone();
+            Box::pin(async move {
+                let data = data.await;
+                if let Some(store) = store {
+                    store.record(&key, &data);
+                }
+                page(&app, data)
+            })
+        };
+        self.insert_page(path, Page::Loaded(Rc::new(load)));
+    }
+
+    fn insert_page(&mut self, path: &str, page: Page<S>) {
         self.handler.insert(path.to_string(), page).unwrap();
+        self.routes.push(path.to_string());
+    }
+
+    /// Retrieves the registered route patterns, in the order they were inserted.
+    pub fn routes(&self) -> &[String] {
+        &self.routes
+    }
+
+    /// Sets the provider listing the concrete paths of a route with parameters.
+    ///
+    /// Static site generation can only render routes without parameters on its own. For a route
+    /// like `/posts/:id`, the provider returns the paths to generate, eg. `/posts/1` and `/posts/2`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hirola::prelude::router::Router;
+    /// use hirola::prelude::*;
+    ///
+    /// fn post_page(_: &App<()>) -> Dom {
+    ///     html! { <h1>"Post"</h1> }
+    /// }
+    ///
+    /// let mut router = Router::<()>::new();
+    /// router.insert("/posts/:id", post_page);
+    /// router.set_static_paths("/posts/:id", |_| {
+    ///     (1..=3).map(|id| format!("/posts/{id}")).collect()
+    /// });
+    /// ```
+    pub fn set_static_paths(
+        &mut self,
+        route: &str,
+        provider: impl Fn(&App<S>) -> Vec<String> + 'static,
+    ) {
+        self.static_paths
+            .insert(route.to_string(), Rc::new(provider));
+    }
+
+    /// Lists the concrete paths of all routes, using the static paths providers for routes with
+    /// parameters. Routes with parameters and no provider are skipped.
+    pub fn static_paths(&self, app: &App<S>) -> Vec<String> {
+        let mut paths = Vec::new();
+        for route in &self.routes {
+            let has_params = route
+                .split('/')
+                .any(|segment| segment.starts_with(':') || segment.starts_with('*'));
+            match self.static_paths.get(route) {
+                Some(provider) => paths.extend(provider(app)),
+                None if has_params => {
+                    log::warn!("Skipping {route}, it has parameters but no static paths")
+                }
+                None => paths.push(route.clone()),
+            }
+        }
+        paths
     }
 
     /// Sets the page rendering function for the not-found route.
@@ -438,6 +1098,7 @@ impl<S: Clone + 'static> Router<S> {
     /// # Returns
     ///
     /// A clone of the route handler, which is an instance of `matchit::Router<fn(&App<S>) -> Dom>`.
+    /// Routes inserted with a loader, eg. with [`Router::insert_with_loader`], are not included.
     ///
     /// # Example
     ///
@@ -467,6 +1128,15 @@ impl<S: Clone + 'static> Router<S> {
     /// let cloned_handler = router.handler();
     /// ```
     pub fn handler(&self) -> matchit::Router<fn(&App<S>) -> Dom> {
-        self.handler.clone()
+        let mut handler = matchit::Router::new();
+        for route in &self.routes {
+            // A pattern matches its own route
+            if let Ok(matched) = self.handler.at(route) {
+                if let Page::Static(page) = matched.value {
+                    handler.insert(route.clone(), *page).unwrap();
+                }
+            }
+        }
+        handler
     }
 }
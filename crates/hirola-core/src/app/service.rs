@@ -0,0 +1,381 @@
+//! A [`tower`](https://docs.rs/tower) service rendering an [`App`] on the server.
+//!
+//! [`AppService`] maps each `GET` or `HEAD` request to [`App::render_document`], keeping the status
+//! and headers set by the router and the pages. It works with any server built on `tower`, eg. as
+//! an `axum` fallback:
+//!
+//! ```ignore
+//! let service = AppService::new(|_parts| create_app()).fallback(ServeDir::new("dist"));
+//! let router = axum::Router::new().fallback_service(service);
+//! ```
+//!
+//! [`App`] is not `Send`, so requests are rendered on a fixed number of threads, see
+//! [`AppService::threads`]. The app is created by the factory on the render thread. Once the page is
+//! rendered, the response is sent back with the status and headers, and the HTML of the page is
+//! streamed to its body in chunks of about 8KiB as it is serialized.
+//!
+//! _This API requires the following crate features to be activated: `tower`_
+
+use super::{
+    pool::{default_threads, Workers},
+    App,
+};
+use crate::document::Shell;
+use crate::generic_node::{
+    ssr_node::{HtmlWriter, CHUNK_SIZE},
+    SsrNode,
+};
+use bytes::Bytes;
+use futures_channel::{mpsc, oneshot};
+use futures_util::StreamExt;
+use http::{header, request::Parts, HeaderName, HeaderValue, Method, Request, Response};
+use http_body::Frame;
+use http_body_util::Either;
+use std::{
+    convert::Infallible,
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+use tower_service::Service;
+
+/// A service rendering the [`App`] created for each request by a factory.
+///
+/// The factory receives the request head, so it can build per-request state from headers, cookies
+/// or extensions.
+///
+/// # Example
+///
+/// ```
+/// use hirola::prelude::*;
+/// use hirola_core::app::service::AppService;
+///
+/// fn home(app: &App<String>) -> Dom {
+///     let user = app.state().clone();
+///     html! { <h1>{user}</h1> }
+/// }
+///
+/// let service = AppService::new(|parts: &http::request::Parts| {
+///     let user = parts
+///         .headers
+///         .get("x-user")
+///         .and_then(|user| user.to_str().ok())
+///         .unwrap_or("guest")
+///         .to_string();
+///     let mut app = App::new(user);
+///     app.route("/", home);
+///     app
+/// });
+/// ```
+pub struct AppService<F, Fb = NoFallback> {
+    factory: Arc<F>,
+    shell: Arc<Shell>,
+    fallback: Option<Fb>,
+    workers: Workers<()>,
+}
+
+impl<F> AppService<F> {
+    /// Creates a service rendering the apps built by `factory` into the default [`Shell`].
+    ///
+    /// Requests are rendered on one thread per available CPU.
+    pub fn new(factory: F) -> Self {
+        Self {
+            factory: Arc::new(factory),
+            shell: Arc::new(Shell::default()),
+            fallback: None,
+            workers: Workers::new(default_threads(), || ()),
+        }
+    }
+}
+
+impl<F, Fb> AppService<F, Fb> {
+    /// Sets the document the pages are rendered into.
+    pub fn shell(mut self, shell: Shell) -> Self {
+        self.shell = Arc::new(shell);
+        self
+    }
+
+    /// Sets the number of threads rendering the requests, queuing the requests beyond it.
+    ///
+    /// The threads are shared by the clones of the service created afterwards.
+    ///
+    /// # Panics
+    ///
+    /// If `threads` is zero.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.workers = Workers::new(threads, || ());
+        self
+    }
+
+    /// Sets the service handling the requests matching no route, eg. one serving static assets.
+    ///
+    /// Requests with methods other than `GET` and `HEAD` are also handled by the fallback. Without a
+    /// fallback, unmatched paths render the not-found page of the app.
+    pub fn fallback<T>(self, fallback: T) -> AppService<F, T> {
+        AppService {
+            factory: self.factory,
+            shell: self.shell,
+            fallback: Some(fallback),
+            workers: self.workers,
+        }
+    }
+}
+
+impl<F, Fb: Clone> Clone for AppService<F, Fb> {
+    fn clone(&self) -> Self {
+        Self {
+            factory: Arc::clone(&self.factory),
+            shell: Arc::clone(&self.shell),
+            fallback: self.fallback.clone(),
+            workers: self.workers.clone(),
+        }
+    }
+}
+
+impl<F, Fb> fmt::Debug for AppService<F, Fb> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppService")
+            .field("shell", &self.shell)
+            .field("fallback", &self.fallback.is_some())
+            .field("threads", &self.workers.threads)
+            .finish()
+    }
+}
+
+/// The outcome of a request on the render thread.
+enum Rendered {
+    Response(Response<RenderBody>),
+    /// The path matched no route, the request is handed back for the fallback.
+    Unmatched(Parts),
+}
+
+impl<F, S, Fb, B, FbBody> Service<Request<B>> for AppService<F, Fb>
+where
+    F: Fn(&Parts) -> App<S> + Send + Sync + 'static,
+    S: Clone + 'static,
+    Fb: Service<Request<B>, Response = Response<FbBody>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    Fb::Future: Send,
+    B: Send + 'static,
+{
+    type Response = Response<Either<RenderBody, FbBody>>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        // The fallback is readied when it is used
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let (parts, body) = request.into_parts();
+        let fallback = self.fallback.clone();
+        if parts.method != Method::GET && parts.method != Method::HEAD {
+            return Box::pin(async move {
+                match fallback {
+                    Some(fallback) => {
+                        call_fallback(fallback, Request::from_parts(parts, body)).await
+                    }
+                    None => Ok(method_not_allowed()),
+                }
+            });
+        }
+        let rendered = render(
+            &self.workers,
+            Arc::clone(&self.factory),
+            Arc::clone(&self.shell),
+            parts,
+            fallback.is_some(),
+        );
+        Box::pin(async move {
+            match rendered.await {
+                Ok(Rendered::Response(response)) => Ok(response.map(Either::Left)),
+                Ok(Rendered::Unmatched(parts)) => {
+                    let fallback = fallback.expect("unmatched paths are handed back to a fallback");
+                    call_fallback(fallback, Request::from_parts(parts, body)).await
+                }
+                // The render thread panicked
+                Err(_) => Ok(Response::builder()
+                    .status(500)
+                    .body(Either::Left(RenderBody::empty()))
+                    .unwrap()),
+            }
+        })
+    }
+}
+
+async fn call_fallback<Fb, B, FbBody>(
+    mut fallback: Fb,
+    request: Request<B>,
+) -> Result<Response<Either<RenderBody, FbBody>>, Infallible>
+where
+    Fb: Service<Request<B>, Response = Response<FbBody>, Error = Infallible>,
+{
+    poll_fn(|cx| fallback.poll_ready(cx)).await?;
+    let response = fallback.call(request).await?;
+    Ok(response.map(Either::Right))
+}
+
+fn method_not_allowed<FbBody>() -> Response<Either<RenderBody, FbBody>> {
+    Response::builder()
+        .status(405)
+        .header(header::ALLOW, "GET, HEAD")
+        .body(Either::Left(RenderBody::empty()))
+        .unwrap()
+}
+
+/// Renders the request on one of the workers, since the app cannot be sent between threads.
+///
+/// The response is sent back once the page is rendered, then the worker streams the HTML of the
+/// page to its body.
+fn render<F, S>(
+    workers: &Workers<()>,
+    factory: Arc<F>,
+    shell: Arc<Shell>,
+    parts: Parts,
+    has_fallback: bool,
+) -> impl Future<Output = Result<Rendered, oneshot::Canceled>> + Send
+where
+    F: Fn(&Parts) -> App<S> + Send + Sync + 'static,
+    S: Clone + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    // The worker drops the sender if it panics, failing the request
+    workers.spawn(move |_| {
+        let app = factory(&parts);
+        let path = parts.uri.path();
+        if has_fallback && app.router().handler.at(path).is_err() {
+            let _ = sender.send(Rendered::Unmatched(parts));
+            return;
+        }
+        let (rendered, tree) = app.render_tree(path);
+        let mut response = Response::builder()
+            .status(rendered.status)
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8");
+        for (name, value) in &rendered.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::try_from(value.as_str()),
+            ) {
+                response = response.header(name, value);
+            }
+        }
+        if parts.method == Method::HEAD {
+            let response = response
+                .body(RenderBody::empty())
+                .expect("invalid status code");
+            let _ = sender.send(Rendered::Response(response));
+            return;
+        }
+        let (chunks, body) = mpsc::unbounded();
+        let response = response
+            .body(RenderBody { chunks: Some(body) })
+            .expect("invalid status code");
+        if sender.send(Rendered::Response(response)).is_err() {
+            return;
+        }
+        stream_document(&shell, &rendered.head, &tree, &chunks);
+    });
+    receiver
+}
+
+/// Sends the document of a page to `chunks`, serializing the body as it is sent.
+fn stream_document(
+    shell: &Shell,
+    head: &str,
+    tree: &SsrNode,
+    chunks: &mpsc::UnboundedSender<Bytes>,
+) {
+    let send = |chunk: String| chunk.is_empty() || chunks.unbounded_send(chunk.into()).is_ok();
+    let (before, after) = shell.split(head);
+    if !send(before) {
+        return;
+    }
+    let Some(after) = after else {
+        // The shell has no body placeholder
+        return;
+    };
+    let mut html = HtmlWriter::new(tree, false);
+    let mut chunk = String::with_capacity(CHUNK_SIZE);
+    loop {
+        let more = html.write_chunk(&mut chunk).unwrap_or_default();
+        // Stop serializing once the client is gone
+        if !send(std::mem::take(&mut chunk)) {
+            return;
+        }
+        if !more {
+            break;
+        }
+    }
+    send(after);
+}
+
+/// The body of a rendered page, streamed from the render thread as the page is serialized.
+#[derive(Debug)]
+pub struct RenderBody {
+    chunks: Option<mpsc::UnboundedReceiver<Bytes>>,
+}
+
+impl RenderBody {
+    fn empty() -> Self {
+        Self { chunks: None }
+    }
+}
+
+impl http_body::Body for RenderBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        let Some(chunks) = self.chunks.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let chunk = ready!(chunks.poll_next_unpin(cx));
+        if chunk.is_none() {
+            self.chunks = None;
+        }
+        Poll::Ready(chunk.map(|chunk| Ok(Frame::data(chunk))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.chunks.is_none()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        match self.chunks {
+            Some(_) => http_body::SizeHint::default(),
+            None => http_body::SizeHint::with_exact(0),
+        }
+    }
+}
+
+/// The fallback of an [`AppService`] without one, responding with `404 Not Found`.
+///
+/// Unmatched paths render the not-found page of the app instead, so it is only used as a type.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFallback;
+
+impl<B> Service<Request<B>> for NoFallback {
+    type Response = Response<RenderBody>;
+    type Error = Infallible;
+    type Future = std::future::Ready<Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _request: Request<B>) -> Self::Future {
+        let response = Response::builder()
+            .status(404)
+            .body(RenderBody::empty())
+            .unwrap();
+        std::future::ready(Ok(response))
+    }
+}
@@ -0,0 +1,95 @@
+//! Transferring the server state to the client.
+//!
+//! On the server, [`App::render_with_state`](super::App::render_with_state) embeds the app state and
+//! the data resolved by embedded loaders in a `<script type="application/json">` tag. On the client,
+//! [`App::from_embedded_state`](super::App::from_embedded_state) restores them so the first render
+//! matches the server's.
+//!
+//! _This API requires the following crate features to be activated: `serde`_
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// The id of the script tag holding the embedded state.
+pub const STATE_ELEMENT_ID: &str = "hirola-state";
+
+/// The serialized form of the embedded state.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct EmbeddedState<S> {
+    pub state: S,
+    #[serde(default)]
+    pub data: HashMap<String, serde_json::Value>,
+}
+
+/// Loader data keyed by route path.
+///
+/// On the server, data resolved by loaders is recorded. On the client, the embedded data is used
+/// once instead of running the loader again.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LoaderData {
+    entries: Rc<RefCell<HashMap<String, serde_json::Value>>>,
+    record: bool,
+}
+
+impl LoaderData {
+    /// Creates a store recording the data resolved while rendering on the server.
+    #[cfg(feature = "ssr")]
+    pub fn recording() -> Self {
+        Self {
+            record: true,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a store with the data embedded by the server.
+    pub fn embedded(entries: HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            entries: Rc::new(RefCell::new(entries)),
+            record: false,
+        }
+    }
+
+    /// Takes the data embedded for `path`, if any.
+    pub fn take<T: DeserializeOwned>(&self, path: &str) -> Option<T> {
+        let value = self.entries.borrow_mut().remove(path)?;
+        match serde_json::from_value(value) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                log::warn!("Ignoring invalid embedded data for {path}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Records the data resolved for `path` if rendering on the server.
+    pub fn record<T: Serialize>(&self, path: &str, data: &T) {
+        if !self.record {
+            return;
+        }
+        match serde_json::to_value(data) {
+            Ok(value) => {
+                self.entries.borrow_mut().insert(path.to_string(), value);
+            }
+            Err(e) => log::warn!("Could not embed the data for {path}: {e}"),
+        }
+    }
+
+    /// Returns the recorded data.
+    #[cfg(feature = "ssr")]
+    pub fn entries(&self) -> HashMap<String, serde_json::Value> {
+        self.entries.borrow().clone()
+    }
+}
+
+/// Serializes `state` into a script tag, escaping `<` so the JSON cannot close the tag.
+#[cfg(feature = "ssr")]
+pub(crate) fn to_script<S: Serialize>(
+    state: &EmbeddedState<S>,
+) -> Result<String, serde_json::Error> {
+    let json = serde_json::to_string(state)?
+        .replace('<', "\\u003c")
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029");
+    Ok(format!(
+        r#"<script type="application/json" id="{STATE_ELEMENT_ID}">{json}</script>"#
+    ))
+}
@@ -0,0 +1,183 @@
+//! Static site generation for [`App`] routes.
+//!
+//! _Generating files requires the following crate features to be activated: `ssr`_
+#[cfg(feature = "ssr")]
+use super::router::Page;
+use super::App;
+#[cfg(feature = "ssr")]
+use crate::document::Shell;
+#[cfg(feature = "ssr")]
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Returns the file a route path is written to, eg. `about/index.html` for `/about`.
+///
+/// Empty segments are skipped. Paths with `.`, `..` or backslashes in a segment are rejected, so that
+/// files are only written inside `out_dir`.
+#[cfg(feature = "ssr")]
+fn output_file(out_dir: &Path, path: &str) -> Option<PathBuf> {
+    let mut file = out_dir.to_path_buf();
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if segment == "." || segment == ".." || segment.contains('\\') {
+            return None;
+        }
+        file.push(segment);
+    }
+    Some(file.join("index.html"))
+}
+
+/// Escapes the characters that are not allowed in XML text.
+#[cfg(feature = "ssr")]
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+impl<S: Clone + 'static> App<S> {
+    /// Set the URL the site is served from, eg. `https://example.com`.
+    ///
+    /// It is used for the absolute URLs of the sitemap written by [`App::generate_static`].
+    pub fn set_site_url(&mut self, url: &str) {
+        self.site_url = Some(url.trim_end_matches('/').to_string());
+    }
+
+    /// Set the provider listing the concrete paths of a route with parameters.
+    ///
+    /// See [`Router::set_static_paths`](super::router::Router::set_static_paths).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    ///
+    /// fn post_page(_: &App<()>) -> Dom {
+    ///     html! { <h1>"Post"</h1> }
+    /// }
+    ///
+    /// let mut app = App::new(());
+    /// app.route("/posts/:id", post_page);
+    /// app.static_paths("/posts/:id", |_| vec!["/posts/hello".to_string()]);
+    /// ```
+    pub fn static_paths(
+        &mut self,
+        route: &str,
+        provider: impl Fn(&App<S>) -> Vec<String> + 'static,
+    ) {
+        self.router.set_static_paths(route, provider);
+    }
+
+    /// Renders the application to static HTML documents in `out_dir`.
+    ///
+    /// Every route without parameters is rendered, along with the paths listed by the
+    /// [static paths providers](App::static_paths) and the extra `paths`. Each path is written to an
+    /// `index.html` filled into `shell`, eg. `/about` to `about/index.html`. Redirects are written as
+    /// documents that refresh to their location. Paths with `.` or `..` segments are skipped with a
+    /// warning.
+    ///
+    /// The not-found page is written to `404.html`, and the successfully rendered paths are listed in
+    /// `sitemap.xml`, using the URL set with [`App::set_site_url`].
+    ///
+    /// # Arguments
+    ///
+    /// * `out_dir` - The directory to write the files to, created if missing.
+    /// * `shell` - The document the pages are rendered into, see [`Shell`].
+    /// * `paths` - Extra paths to render, eg. pages only reachable from links.
+    ///
+    /// # Returns
+    ///
+    /// The rendered paths.
+    ///
+    /// _This API requires the following crate features to be activated: `ssr`_
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hirola::prelude::*;
+    /// use hirola_core::document::Shell;
+    ///
+    /// fn home_page(_: &App<()>) -> Dom {
+    ///     html! { <h1>"Home"</h1> }
+    /// }
+    ///
+    /// let mut app = App::new(());
+    /// app.route("/", home_page);
+    /// app.set_site_url("https://example.com");
+    /// app.generate_static("dist", &Shell::default(), &[]).unwrap();
+    /// ```
+    #[cfg(feature = "ssr")]
+    pub fn generate_static(
+        &self,
+        out_dir: impl AsRef<Path>,
+        shell: &Shell,
+        paths: &[&str],
+    ) -> io::Result<Vec<String>> {
+        let out_dir = out_dir.as_ref();
+        let mut all_paths = self.router.static_paths(self);
+        all_paths.extend(paths.iter().map(|path| path.to_string()));
+        let mut generated = Vec::new();
+        let mut sitemap = Vec::new();
+        for path in all_paths {
+            if generated.contains(&path) {
+                continue;
+            }
+            let Some(file) = output_file(out_dir, &path) else {
+                log::warn!("Skipping the path {path} which leaves the output directory");
+                continue;
+            };
+            let response = self.render_response(&path);
+            let document = match response.header("Location") {
+                Some(location) if response.is_redirect() => shell.fill(
+                    &format!(
+                        r#"<meta http-equiv="refresh" content="0; url={}">"#,
+                        escape_xml(location)
+                    ),
+                    "",
+                ),
+                _ => shell.fill(&response.head, &response.body),
+            };
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, document)?;
+            if (200..300).contains(&response.status) {
+                sitemap.push(path.clone());
+            }
+            generated.push(path);
+        }
+
+        fs::create_dir_all(out_dir)?;
+        let not_found = futures_executor::block_on(self.render_page(
+            "/404",
+            Page::Static(*self.router.not_found),
+            404,
+        ));
+        fs::write(
+            out_dir.join("404.html"),
+            shell.fill(&not_found.head, &not_found.body),
+        )?;
+        fs::write(out_dir.join("sitemap.xml"), self.sitemap(&sitemap))?;
+        Ok(generated)
+    }
+
+    /// Builds a sitemap listing `paths`.
+    #[cfg(feature = "ssr")]
+    fn sitemap(&self, paths: &[String]) -> String {
+        if self.site_url.is_none() {
+            log::warn!("No site URL set, the sitemap will have relative URLs");
+        }
+        let base = self.site_url.as_deref().unwrap_or_default();
+        let urls: String = paths
+            .iter()
+            .map(|path| {
+                format!(
+                    "<url><loc>{}</loc></url>",
+                    escape_xml(&format!("{base}{path}"))
+                )
+            })
+            .collect();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{urls}</urlset>"#
+        )
+    }
+}
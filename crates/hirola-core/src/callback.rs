@@ -1,6 +1,8 @@
 use futures_signals::{signal::Mutable, signal_vec::MutableVec};
-use web_sys::Event;
+use crate::generic_node::Event;
 
+/// Creates event handlers updating a signal, with the same signature for the `dom` and `ssr`
+/// backends. On the server the handlers are never called.
 pub trait Callback<T> {
     /// Pass a callback that allows interacting with the inner value and the dom event
     /// This method returns the new value and this updates the signal.
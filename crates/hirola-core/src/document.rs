@@ -0,0 +1,149 @@
+//! Rendering complete HTML documents on the server.
+//!
+//! A [`Shell`] is the HTML template of the page around the rendered body. It contains placeholders
+//! that are filled when rendering:
+//! - [`HEAD_PLACEHOLDER`] with the head entries set by [`Title`](crate::head::Title),
+//!   [`Meta`](crate::head::Meta) and [`HeadLink`](crate::head::HeadLink),
+//! - [`BODY_PLACEHOLDER`] with the rendered body, including the state embedded by
+//!   `App::render_with_state`,
+//! - [`SCRIPTS_PLACEHOLDER`] with the script tags added by [`Shell::script`].
+//!
+//! _This API requires the following crate features to be activated: `ssr`_
+
+use crate::{dom::Dom, head, render_to_string};
+
+/// Replaced by the head entries.
+pub const HEAD_PLACEHOLDER: &str = "<!--hirola-head-->";
+
+/// Replaced by the rendered body.
+pub const BODY_PLACEHOLDER: &str = "<!--hirola-body-->";
+
+/// Replaced by the script tags.
+pub const SCRIPTS_PLACEHOLDER: &str = "<!--hirola-scripts-->";
+
+const DEFAULT_TEMPLATE: &str = concat!(
+    "<!DOCTYPE html>",
+    r#"<html><head><meta charset="utf-8">"#,
+    r#"<meta name="viewport" content="width=device-width, initial-scale=1">"#,
+    "<!--hirola-head--></head>",
+    "<body><!--hirola-body--><!--hirola-scripts--></body></html>"
+);
+
+/// The HTML template of a document.
+///
+/// # Example
+///
+/// ```
+/// use hirola::prelude::*;
+/// use hirola_core::document::Shell;
+///
+/// let shell = Shell::new(
+///     r#"<!DOCTYPE html><html lang="en"><head><!--hirola-head--></head><body><!--hirola-body--><!--hirola-scripts--></body></html>"#,
+/// )
+/// .script("/pkg/app.js");
+///
+/// let html = render_document(&shell, || {
+///     html! {
+///         <main>
+///             <Title text="Home" />
+///             <h1>"Home"</h1>
+///         </main>
+///     }
+/// });
+/// assert_eq!(
+///     html,
+///     concat!(
+///         r#"<!DOCTYPE html><html lang="en"><head><title data-hirola-head="title">Home</title></head>"#,
+///         r#"<body><main><h1>Home</h1></main><script type="module" src="/pkg/app.js"></script></body></html>"#
+///     )
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shell {
+    template: String,
+    scripts: Vec<String>,
+}
+
+impl Shell {
+    /// Creates a shell from an HTML template containing the placeholders.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            scripts: Vec::new(),
+        }
+    }
+
+    /// Adds a module script loaded from `src`, eg. the client bundle hydrating the page.
+    pub fn script(mut self, src: &str) -> Self {
+        self.scripts.push(format!(
+            r#"<script type="module" src="{}"></script>"#,
+            html_escape::encode_double_quoted_attribute(src)
+        ));
+        self
+    }
+
+    /// Fills the placeholders of the template with the `head` and `body` HTML.
+    ///
+    /// Placeholders missing from the template are skipped.
+    pub fn fill(&self, head: &str, body: &str) -> String {
+        let (mut html, rest) = self.split(head);
+        if let Some(rest) = rest {
+            html.push_str(body);
+            html.push_str(&rest);
+        }
+        html
+    }
+
+    /// Fills the template with the `head` HTML, returning the parts of the document before and after the
+    /// body, or only the filled template if it has no body placeholder.
+    pub(crate) fn split(&self, head: &str) -> (String, Option<String>) {
+        let scripts = self.scripts.concat();
+        let mut parts: Vec<(usize, &str, Option<&str>)> = [
+            (HEAD_PLACEHOLDER, Some(head)),
+            (BODY_PLACEHOLDER, None),
+            (SCRIPTS_PLACEHOLDER, Some(scripts.as_str())),
+        ]
+        .into_iter()
+        .filter_map(|(placeholder, value)| {
+            self.template
+                .find(placeholder)
+                .map(|index| (index, placeholder, value))
+        })
+        .collect();
+        // Fill in a single pass so the inserted HTML is never searched for placeholders
+        parts.sort_by_key(|(index, _, _)| *index);
+        let mut html = String::with_capacity(self.template.len() + head.len());
+        let mut before = None;
+        let mut rest = 0;
+        for (index, placeholder, value) in parts {
+            html.push_str(&self.template[rest..index]);
+            match value {
+                Some(value) => html.push_str(value),
+                None => before = Some(std::mem::take(&mut html)),
+            }
+            rest = index + placeholder.len();
+        }
+        html.push_str(&self.template[rest..]);
+        match before {
+            Some(before) => (before, Some(html)),
+            None => (html, None),
+        }
+    }
+}
+
+impl Default for Shell {
+    /// A minimal HTML5 document with a charset and viewport.
+    fn default() -> Self {
+        Self::new(DEFAULT_TEMPLATE)
+    }
+}
+
+/// Renders the [`Dom`] built by `render` into a complete document using `shell`.
+///
+/// The head entries set while building the [`Dom`] are inserted in the head of the document.
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+pub fn render_document(shell: &Shell, render: impl FnOnce() -> Dom) -> String {
+    let (body, head) = head::collect(|| render_to_string(render()));
+    shell.fill(&head, &body)
+}
@@ -1,5 +1,5 @@
 use crate::{
-    generic_node::{DomType, GenericNode},
+    generic_node::{backend, Backend, DomType, GenericNode},
     render::{Error, Render},
     spawn, BoxedLocal,
 };
@@ -14,7 +14,6 @@ use web_sys::HtmlElement;
 #[cfg(feature = "dom")]
 use wasm_bindgen::prelude::Closure;
 
-#[cfg(feature = "dom")]
 use crate::generic_node::EventListener;
 
 pub enum DomSideEffect {
@@ -22,15 +21,16 @@ pub enum DomSideEffect {
     Mounted(CancelableFutureHandle),
 }
 
+/// Functions run once when a [`Dom`] is discarded.
+type Cleanups = Rc<RefCell<Vec<Box<dyn FnOnce()>>>>;
+
 #[derive(Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
 pub struct Dom {
     node: DomType,
-    #[cfg_attr(feature = "serde", serde(skip))]
     pub side_effects: Rc<RefCell<Vec<DomSideEffect>>>,
     #[cfg(feature = "dom")]
-    #[cfg_attr(feature = "serde", serde(skip))]
     event_handlers: Rc<RefCell<Vec<Closure<EventListener>>>>,
+    cleanups: Cleanups,
     children: RefCell<Vec<Dom>>,
 }
 
@@ -68,15 +68,20 @@ impl Dom {
             #[cfg(feature = "dom")]
             event_handlers: Default::default(),
             side_effects: Default::default(),
+            cleanups: Default::default(),
         }
     }
 
-    #[cfg(feature = "dom")]
+    /// Adds an event listener, handlers are ignored when rendering on the server.
     #[inline]
+    #[allow(unused_variables)]
     pub fn event(&self, name: &str, handler: Box<EventListener>) {
-        let closure = self.node.event(name, handler);
-        if let Some(closure) = closure {
-            self.event_handlers.borrow_mut().push(closure);
+        #[cfg(feature = "dom")]
+        {
+            let closure = self.node.event(name, handler);
+            if let Some(closure) = closure {
+                self.event_handlers.borrow_mut().push(closure);
+            }
         }
     }
 
@@ -91,6 +96,11 @@ impl Dom {
             .push(DomSideEffect::Mounted(DiscardOnDrop::leak(spawn(future))));
     }
 
+    /// Registers a function to run when the dom is discarded, eg. to remove global event listeners.
+    pub fn on_cleanup(&self, cleanup: impl FnOnce() + 'static) {
+        self.cleanups.borrow_mut().push(Box::new(cleanup));
+    }
+
     pub fn append_render(&self, render: impl Render + 'static) {
         Box::new(render).render_into(&self).unwrap();
     }
@@ -117,6 +127,9 @@ impl Dom {
                 }
             })
             .collect();
+        for cleanup in self.cleanups.take() {
+            cleanup();
+        }
     }
 
     pub fn mount(self, node: &DomType) -> Result<Dom, Error> {
@@ -126,26 +139,35 @@ impl Dom {
     }
 
     pub fn inner_html(&self) -> String {
-        #[cfg(feature = "dom")]
-        {
-            let window = web_sys::window().unwrap();
-            let document = window.document().unwrap();
-            let element = document.create_element("div").unwrap();
-
-            let dom = crate::render_to(self.clone(), &element.try_into().unwrap()).unwrap();
-            return dom
-                .node()
-                .inner_element()
-                .dyn_ref::<HtmlElement>()
-                .unwrap()
-                .inner_html();
+        match backend() {
+            #[cfg(feature = "dom")]
+            Backend::Dom => {
+                let window = web_sys::window().unwrap();
+                let document = window.document().unwrap();
+                let element = document.create_element("div").unwrap();
+
+                let dom = crate::render_to(self.clone(), &element.try_into().unwrap()).unwrap();
+                let node = crate::generic_node::as_dom(dom.node()).inner_element();
+                node.dyn_ref::<HtmlElement>().unwrap().inner_html()
+            }
+            #[cfg(feature = "ssr")]
+            Backend::Ssr => crate::render_to_string(self.clone()),
         }
+    }
+}
 
-        #[cfg(feature = "ssr")]
-        #[allow(unreachable_code)]
-        {
-            return crate::render_to_string(self.clone());
-        }
+/// Serializes the node tree only, in the format of [`SsrNode`](crate::generic_node::SsrNode).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Dom {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.node.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Dom {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DomType::deserialize(deserializer).map(|node| Dom::new_from_node(&node))
     }
 }
 
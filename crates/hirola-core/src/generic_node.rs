@@ -3,6 +3,11 @@ pub mod dom_node;
 #[cfg(feature = "ssr")]
 pub mod ssr_node;
 
+#[cfg(all(feature = "dom", feature = "ssr"))]
+pub mod any_node;
+
+#[cfg(all(feature = "dom", feature = "ssr"))]
+pub use any_node::{with_backend, AnyNode};
 #[cfg(feature = "dom")]
 pub use dom_node::*;
 #[cfg(feature = "ssr")]
@@ -14,19 +19,182 @@ use wasm_bindgen::prelude::Closure;
 use std::fmt;
 
 #[cfg(feature = "dom")]
-use web_sys::Event;
+pub use web_sys::Event;
 
-#[cfg(feature = "ssr")]
-pub type Event = ();
+/// An event passed to handlers when rendering on the server.
+///
+/// Handlers are accepted and ignored on the server, so this type only lets components handling
+/// events build for both backends. It mirrors the commonly used methods of `web_sys::Event`.
+/// With the `dom` feature, `web_sys::Event` is used by both backends.
+#[cfg(all(feature = "ssr", not(feature = "dom")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    type_: String,
+    // Shared by the clones, like the handles of `web_sys::Event`
+    default_prevented: std::rc::Rc<std::cell::Cell<bool>>,
+}
+
+#[cfg(all(feature = "ssr", not(feature = "dom")))]
+impl Event {
+    /// Creates an event of type `type_`, eg. to call handlers in tests.
+    pub fn new(type_: &str) -> Self {
+        Self {
+            type_: type_.to_string(),
+            default_prevented: Default::default(),
+        }
+    }
+
+    /// Returns the type of the event, eg. `click`.
+    pub fn type_(&self) -> String {
+        self.type_.clone()
+    }
+
+    /// Marks the default action of the event as canceled.
+    pub fn prevent_default(&self) {
+        self.default_prevented.set(true);
+    }
+
+    /// Returns `true` if [`prevent_default`](Self::prevent_default) was called.
+    pub fn default_prevented(&self) -> bool {
+        self.default_prevented.get()
+    }
+
+    /// Does nothing, events do not propagate on the server.
+    pub fn stop_propagation(&self) {}
+
+    /// Does nothing, events do not propagate on the server.
+    pub fn stop_immediate_propagation(&self) {}
+}
 
 pub type EventListener = dyn Fn(Event);
 
-#[cfg(feature = "dom")]
+#[cfg(all(feature = "dom", not(feature = "ssr")))]
 pub type DomType = dom_node::DomNode;
 
-#[cfg(feature = "ssr")]
+#[cfg(all(feature = "ssr", not(feature = "dom")))]
 pub type DomType = ssr_node::SsrNode;
 
+/// With both the `dom` and `ssr` features, the backend is selected at runtime, see [`backend`].
+#[cfg(all(feature = "dom", feature = "ssr"))]
+pub type DomType = any_node::AnyNode;
+
+/// The rendering backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Browser DOM nodes, see [`DomNode`].
+    #[cfg(feature = "dom")]
+    Dom,
+    /// Nodes rendered to strings on the server, see [`SsrNode`].
+    #[cfg(feature = "ssr")]
+    Ssr,
+}
+
+/// Returns the backend new nodes are created with on this thread.
+///
+/// With both the `dom` and `ssr` features, this is [`Backend::Dom`] on `wasm32` targets and
+/// [`Backend::Ssr`] elsewhere, unless changed with [`with_backend`]. Rendering an app on the server,
+/// eg. with `App::render_to_string`, always uses [`Backend::Ssr`].
+#[cfg(all(feature = "dom", feature = "ssr"))]
+pub fn backend() -> Backend {
+    any_node::current_backend()
+}
+
+/// Returns the backend new nodes are created with on this thread.
+#[cfg(all(feature = "dom", not(feature = "ssr")))]
+pub fn backend() -> Backend {
+    Backend::Dom
+}
+
+/// Returns the backend new nodes are created with on this thread.
+#[cfg(all(feature = "ssr", not(feature = "dom")))]
+pub fn backend() -> Backend {
+    Backend::Ssr
+}
+
+/// Creates the nodes on this thread with the SSR backend until dropped.
+#[cfg(feature = "ssr")]
+#[must_use]
+pub(crate) struct SsrScope {
+    #[cfg(feature = "dom")]
+    previous: Backend,
+}
+
+#[cfg(feature = "ssr")]
+impl SsrScope {
+    pub(crate) fn enter() -> Self {
+        Self {
+            #[cfg(feature = "dom")]
+            previous: any_node::set_backend(Backend::Ssr),
+        }
+    }
+}
+
+#[cfg(all(feature = "dom", feature = "ssr"))]
+impl Drop for SsrScope {
+    fn drop(&mut self) {
+        any_node::set_backend(self.previous);
+    }
+}
+
+/// Wraps a server node as a [`DomType`].
+#[cfg(feature = "ssr")]
+pub(crate) fn from_ssr(node: SsrNode) -> DomType {
+    #[cfg(feature = "dom")]
+    return AnyNode::Ssr(node);
+    #[cfg(not(feature = "dom"))]
+    node
+}
+
+/// Wraps a browser node as a [`DomType`].
+#[cfg(feature = "dom")]
+pub(crate) fn from_dom(node: DomNode) -> DomType {
+    #[cfg(feature = "ssr")]
+    return AnyNode::Dom(node);
+    #[cfg(not(feature = "ssr"))]
+    node
+}
+
+/// Returns the browser node of a [`DomType`], `None` if it was created with the SSR backend.
+///
+/// Mixins that need the underlying DOM element can use this to stay inert on the server when
+/// both the `dom` and `ssr` features are activated.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+#[cfg(feature = "dom")]
+pub fn dom_node(node: &DomType) -> Option<&DomNode> {
+    #[cfg(feature = "ssr")]
+    return node.as_dom();
+    #[cfg(not(feature = "ssr"))]
+    Some(node)
+}
+
+/// Returns the server node of a [`DomType`], `None` if it was created with the DOM backend.
+///
+/// Useful to query trees rendered on the server, eg. in tests, when both the `dom` and `ssr`
+/// features are activated.
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+#[cfg(feature = "ssr")]
+pub fn ssr_node(node: &DomType) -> Option<&SsrNode> {
+    #[cfg(feature = "dom")]
+    return node.as_ssr();
+    #[cfg(not(feature = "dom"))]
+    Some(node)
+}
+
+/// Returns the browser node of a [`DomType`].
+///
+/// # Panics
+///
+/// If the node was created with the SSR backend.
+#[cfg(feature = "dom")]
+pub(crate) fn as_dom(node: &DomType) -> &DomNode {
+    #[cfg(feature = "ssr")]
+    return dom_node(node).expect("node is not a DOM node");
+    #[cfg(not(feature = "ssr"))]
+    node
+}
+
 pub trait GenericNode: fmt::Debug + Clone + PartialEq + std::cmp::Eq + 'static {
     /// Create a new element node.
     fn element(tag: &str) -> Self;
@@ -42,9 +210,19 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + std::cmp::Eq + 'static {
     /// end of the list. If the list is empty, a dummy node is needed to store the position of the component.
     fn marker() -> Self;
 
+    /// Create a document type declaration, eg. `<!DOCTYPE html>` for `doctype("html")`.
+    /// For [`DomNode`], this creates an empty fragment since the document already has a doctype.
+    fn doctype(value: &str) -> Self;
+
     /// Sets an attribute on a node.
     fn set_attribute(&self, name: &str, value: &str);
 
+    /// Removes an attribute from a node.
+    fn remove_attribute(&self, name: &str);
+
+    /// Returns the value of an attribute, or `None` if it is not set.
+    fn get_attribute(&self, name: &str) -> Option<String>;
+
     /// Appends a child to the node's children.
     fn append_child(&self, child: &Self);
 
@@ -79,6 +257,14 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + std::cmp::Eq + 'static {
     /// Update inner text of the node. If the node has elements, all the elements are replaced with a new text node.
     fn update_inner_text(&self, text: &str);
 
+    /// Sets the text content of the node, replacing its children with a single text node.
+    fn set_text_content(&self, text: &str);
+
+    /// Replaces the children of an element with nodes parsed from `html`.
+    ///
+    /// Note: The markup is not sanitized, only pass trusted content.
+    fn set_inner_html(&self, html: &str);
+
     /// Replace all the children in a node with a new node
     fn replace_children_with(&self, node: &Self);
 }
@@ -0,0 +1,303 @@
+use super::{Backend, DomNode, EventListener, GenericNode, HtmlNode, SsrNode};
+use std::{cell::Cell, fmt};
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::Event;
+
+thread_local! {
+    static BACKEND: Cell<Backend> = const {
+        Cell::new(if cfg!(target_arch = "wasm32") {
+            Backend::Dom
+        } else {
+            Backend::Ssr
+        })
+    };
+}
+
+pub(super) fn current_backend() -> Backend {
+    BACKEND.with(Cell::get)
+}
+
+/// Sets the backend of this thread, returning the previous one.
+pub(super) fn set_backend(backend: Backend) -> Backend {
+    BACKEND.with(|current| current.replace(backend))
+}
+
+/// Runs `f` creating the nodes on this thread with `backend`.
+///
+/// With both the `dom` and `ssr` features, the backend is chosen when nodes are created, so views
+/// have to be built inside `f`, eg. to pre-render a page in the browser:
+///
+/// ```no_run
+/// use hirola::prelude::*;
+/// use hirola_core::generic_node::{with_backend, Backend};
+///
+/// let html = with_backend(Backend::Ssr, || render_to_string(html! { <p>"Hello"</p> }));
+/// ```
+///
+/// _This API requires the following crate features to be activated: `dom`, `ssr`_
+pub fn with_backend<R>(backend: Backend, f: impl FnOnce() -> R) -> R {
+    struct Restore(Backend);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            set_backend(self.0);
+        }
+    }
+    let _restore = Restore(set_backend(backend));
+    f()
+}
+
+/// A node of the backend selected at runtime, used when both the `dom` and `ssr` features are
+/// activated.
+///
+/// New nodes use the [`backend`](super::backend) of the current thread. Nodes of different
+/// backends cannot be mixed, such operations are skipped and logged as errors.
+///
+/// _This API requires the following crate features to be activated: `dom`, `ssr`_
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyNode {
+    /// A browser DOM node.
+    Dom(DomNode),
+    /// A node rendered on the server.
+    Ssr(SsrNode),
+}
+
+impl AnyNode {
+    /// Returns the DOM node, `None` for server nodes.
+    pub fn as_dom(&self) -> Option<&DomNode> {
+        match self {
+            AnyNode::Dom(node) => Some(node),
+            AnyNode::Ssr(_) => None,
+        }
+    }
+
+    /// Returns the server node, `None` for DOM nodes.
+    pub fn as_ssr(&self) -> Option<&SsrNode> {
+        match self {
+            AnyNode::Ssr(node) => Some(node),
+            AnyNode::Dom(_) => None,
+        }
+    }
+
+    /// Returns an owned copy of the tree in the stable [`HtmlNode`] format.
+    ///
+    /// DOM nodes are serialized by the browser and parsed back.
+    pub fn to_html_node(&self) -> HtmlNode {
+        match self {
+            AnyNode::Ssr(node) => node.to_html_node(),
+            AnyNode::Dom(_) => SsrNode::parse_html(&self.to_string())
+                .expect("browsers serialize valid HTML")
+                .to_html_node(),
+        }
+    }
+
+    /// Converts the DOM node into a specified type using unchecked casting, see
+    /// [`DomNode::unchecked_into`].
+    ///
+    /// # Panics
+    ///
+    /// If the node was created with the SSR backend.
+    pub fn unchecked_into<T: JsCast>(self) -> T {
+        match self {
+            AnyNode::Dom(node) => node.unchecked_into(),
+            AnyNode::Ssr(_) => panic!("node is not a DOM node"),
+        }
+    }
+}
+
+impl fmt::Display for AnyNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyNode::Ssr(node) => node.fmt(f),
+            AnyNode::Dom(node) => {
+                // Serialize a copy, so that fragments and text nodes are escaped like elements
+                let document = web_sys::window().unwrap().document().unwrap();
+                let container = document.create_element("div").unwrap();
+                let copy = node.inner_element().clone_node_with_deep(true).unwrap();
+                container.append_child(&copy).unwrap();
+                f.write_str(&container.inner_html())
+            }
+        }
+    }
+}
+
+impl From<DomNode> for AnyNode {
+    fn from(node: DomNode) -> Self {
+        AnyNode::Dom(node)
+    }
+}
+
+impl From<SsrNode> for AnyNode {
+    fn from(node: SsrNode) -> Self {
+        AnyNode::Ssr(node)
+    }
+}
+
+/// Creates a node with the backend of the current thread.
+macro_rules! create {
+    ($($create:tt)*) => {
+        match current_backend() {
+            Backend::Dom => AnyNode::Dom(DomNode::$($create)*),
+            Backend::Ssr => AnyNode::Ssr(SsrNode::$($create)*),
+        }
+    };
+}
+
+/// Calls a method on the node, whatever its backend.
+macro_rules! forward {
+    ($node:expr, $method:ident($($arg:expr),*)) => {
+        match $node {
+            AnyNode::Dom(node) => node.$method($($arg),*),
+            AnyNode::Ssr(node) => node.$method($($arg),*),
+        }
+    };
+}
+
+/// Reports an operation on nodes of different backends, which means the backend was picked wrongly.
+fn mixed(operation: &str) {
+    log::error!("Could not {operation}: nodes belong to different backends");
+}
+
+impl GenericNode for AnyNode {
+    fn element(tag: &str) -> Self {
+        create!(element(tag))
+    }
+
+    fn text_node(text: &str) -> Self {
+        create!(text_node(text))
+    }
+
+    fn fragment() -> Self {
+        create!(fragment())
+    }
+
+    fn marker() -> Self {
+        create!(marker())
+    }
+
+    fn doctype(value: &str) -> Self {
+        create!(doctype(value))
+    }
+
+    fn set_attribute(&self, name: &str, value: &str) {
+        forward!(self, set_attribute(name, value))
+    }
+
+    fn remove_attribute(&self, name: &str) {
+        forward!(self, remove_attribute(name))
+    }
+
+    fn get_attribute(&self, name: &str) -> Option<String> {
+        forward!(self, get_attribute(name))
+    }
+
+    fn append_child(&self, child: &Self) {
+        match (self, child) {
+            (AnyNode::Dom(node), AnyNode::Dom(child)) => node.append_child(child),
+            (AnyNode::Ssr(node), AnyNode::Ssr(child)) => node.append_child(child),
+            _ => mixed("append child"),
+        }
+    }
+
+    fn insert_child_before(&self, new_node: &Self, reference_node: Option<&Self>) {
+        match (self, new_node, reference_node) {
+            (AnyNode::Dom(node), AnyNode::Dom(new_node), None) => {
+                node.insert_child_before(new_node, None)
+            }
+            (AnyNode::Dom(node), AnyNode::Dom(new_node), Some(AnyNode::Dom(reference))) => {
+                node.insert_child_before(new_node, Some(reference))
+            }
+            (AnyNode::Ssr(node), AnyNode::Ssr(new_node), None) => {
+                node.insert_child_before(new_node, None)
+            }
+            (AnyNode::Ssr(node), AnyNode::Ssr(new_node), Some(AnyNode::Ssr(reference))) => {
+                node.insert_child_before(new_node, Some(reference))
+            }
+            _ => mixed("insert child"),
+        }
+    }
+
+    fn remove_child(&self, child: &Self) {
+        match (self, child) {
+            (AnyNode::Dom(node), AnyNode::Dom(child)) => node.remove_child(child),
+            (AnyNode::Ssr(node), AnyNode::Ssr(child)) => node.remove_child(child),
+            _ => mixed("remove child"),
+        }
+    }
+
+    fn replace_child(&self, old: &Self, new: &Self) {
+        match (self, old, new) {
+            (AnyNode::Dom(node), AnyNode::Dom(old), AnyNode::Dom(new)) => {
+                node.replace_child(old, new)
+            }
+            (AnyNode::Ssr(node), AnyNode::Ssr(old), AnyNode::Ssr(new)) => {
+                node.replace_child(old, new)
+            }
+            _ => mixed("replace child"),
+        }
+    }
+
+    fn insert_sibling_before(&self, child: &Self) {
+        match (self, child) {
+            (AnyNode::Dom(node), AnyNode::Dom(child)) => node.insert_sibling_before(child),
+            (AnyNode::Ssr(node), AnyNode::Ssr(child)) => node.insert_sibling_before(child),
+            _ => mixed("insert sibling"),
+        }
+    }
+
+    fn parent_node(&self) -> Option<Self> {
+        match self {
+            AnyNode::Dom(node) => node.parent_node().map(AnyNode::Dom),
+            AnyNode::Ssr(node) => node.parent_node().map(AnyNode::Ssr),
+        }
+    }
+
+    fn next_sibling(&self) -> Option<Self> {
+        match self {
+            AnyNode::Dom(node) => node.next_sibling().map(AnyNode::Dom),
+            AnyNode::Ssr(node) => node.next_sibling().map(AnyNode::Ssr),
+        }
+    }
+
+    fn remove_self(&self) {
+        forward!(self, remove_self())
+    }
+
+    fn event(&self, name: &str, handler: Box<EventListener>) -> Option<Closure<dyn Fn(Event)>> {
+        forward!(self, event(name, handler))
+    }
+
+    fn update_inner_text(&self, text: &str) {
+        forward!(self, update_inner_text(text))
+    }
+
+    fn set_text_content(&self, text: &str) {
+        forward!(self, set_text_content(text))
+    }
+
+    fn set_inner_html(&self, html: &str) {
+        forward!(self, set_inner_html(html))
+    }
+
+    fn replace_children_with(&self, node: &Self) {
+        match (self, node) {
+            (AnyNode::Dom(parent), AnyNode::Dom(node)) => parent.replace_children_with(node),
+            (AnyNode::Ssr(parent), AnyNode::Ssr(node)) => parent.replace_children_with(node),
+            _ => mixed("replace children"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AnyNode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        forward!(self, serialize(serializer))
+    }
+}
+
+/// Deserializes the tree format of [`SsrNode`] into a server node.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AnyNode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SsrNode::deserialize(deserializer).map(AnyNode::Ssr)
+    }
+}
@@ -1,6 +1,6 @@
 use super::{EventListener, GenericNode};
 use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::{Element, Event, Node, Text};
+use web_sys::{Element, Event, Node};
 
 /// Rendering backend for the DOM.
 ///
@@ -111,6 +111,10 @@ impl GenericNode for DomNode {
         }
     }
 
+    fn doctype(_value: &str) -> Self {
+        Self::fragment()
+    }
+
     fn set_attribute(&self, name: &str, value: &str) {
         self.node
             .unchecked_ref::<Element>()
@@ -118,6 +122,19 @@ impl GenericNode for DomNode {
             .unwrap();
     }
 
+    fn remove_attribute(&self, name: &str) {
+        self.node
+            .unchecked_ref::<Element>()
+            .remove_attribute(name)
+            .unwrap();
+    }
+
+    fn get_attribute(&self, name: &str) -> Option<String> {
+        self.node
+            .dyn_ref::<Element>()
+            .and_then(|element| element.get_attribute(name))
+    }
+
     fn append_child(&self, child: &Self) {
         match self.node.append_child(&child.node) {
             Err(e) => log::warn!("Could not append child: {e:?}"),
@@ -177,11 +194,20 @@ impl GenericNode for DomNode {
     }
 
     fn update_inner_text(&self, text: &str) {
-        self.node
-            .dyn_ref::<Text>()
-            .unwrap()
-            .set_text_content(Some(text));
+        self.node.set_text_content(Some(text));
+    }
+
+    fn set_text_content(&self, text: &str) {
+        self.node.set_text_content(Some(text));
     }
+
+    fn set_inner_html(&self, html: &str) {
+        match self.node.dyn_ref::<Element>() {
+            Some(element) => element.set_inner_html(html),
+            None => log::warn!("Could not set inner html: node is not an element"),
+        }
+    }
+
     fn replace_children_with(&self, node: &Self) {
         let element = self.node.unchecked_ref::<Element>();
         element.replace_children_with_node_1(&node.inner_element())
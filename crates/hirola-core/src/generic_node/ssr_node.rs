@@ -1,25 +1,30 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{RefCell, RefMut};
 use std::rc::{Rc, Weak};
 use std::{fmt, mem};
 
 use crate::generic_node::GenericNode;
 
+mod ast;
+mod parser;
+mod selector;
+pub use ast::HtmlNode;
+pub use parser::ParseError;
+use selector::Selector;
+pub use selector::SelectorError;
+
 /// Rendering backend for Server Side Rendering, aka. SSR.
 ///
 /// _This API requires the following crate features to be activated: `ssr`_
 #[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
 enum SsrNodeType {
     Element(RefCell<Element>),
     Comment(RefCell<Comment>),
+    Doctype(RefCell<Doctype>),
     Text(RefCell<Text>),
     Fragment(RefCell<Fragment>),
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
-
 struct SsrNodeInner {
     ty: Rc<SsrNodeType>,
     /// No parent if `Weak::upgrade` returns `None`.
@@ -27,7 +32,6 @@ struct SsrNodeInner {
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
 pub struct SsrNode(Rc<SsrNodeInner>);
 
 impl PartialEq for SsrNode {
@@ -46,14 +50,6 @@ impl SsrNode {
         }))
     }
 
-    fn set_parent(&self, parent: Weak<SsrNodeInner>) {
-        if let Some(old_parent) = self.parent_node() {
-            old_parent.try_remove_child(self);
-        }
-
-        *self.0.parent.borrow_mut() = parent;
-    }
-
     #[track_caller]
     fn unwrap_element(&self) -> &RefCell<Element> {
         match self.0.ty.as_ref() {
@@ -62,44 +58,43 @@ impl SsrNode {
         }
     }
 
-    #[track_caller]
-    fn unwrap_text(&self) -> &RefCell<Text> {
-        match &self.0.ty.as_ref() {
-            SsrNodeType::Text(e) => e,
-            _ => panic!("node is not a text node"),
+    /// Returns the children of an element or a fragment, `None` for other nodes.
+    fn children_mut(&self) -> Option<RefMut<'_, Vec<SsrNode>>> {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Element(e) => Some(RefMut::map(e.borrow_mut(), |e| &mut e.children.0)),
+            SsrNodeType::Fragment(f) => Some(RefMut::map(f.borrow_mut(), |f| &mut f.0)),
+            _ => None,
         }
     }
 
-    // FIXME: recursively visit Fragments and call try_remove_child
-    fn try_remove_child(&self, child: &Self) {
-        let mut children = match self.0.ty.as_ref() {
-            SsrNodeType::Element(e) => mem::take(&mut e.borrow_mut().children.0),
-            SsrNodeType::Fragment(f) => mem::take(&mut f.borrow_mut().0),
-            _ => panic!("node type cannot have children"),
-        };
+    fn index_of(&self, child: &Self) -> Option<usize> {
+        self.children_mut()?.iter().position(|c| c == child)
+    }
 
-        if let Some(index) = children
-            .iter()
-            .enumerate()
-            .find_map(|(i, c)| (c == child).then(|| i))
-        {
-            children.remove(index);
-        } else {
-            // try remove from child Fragments
-            for c in &children {
-                if let SsrNodeType::Fragment(fragment) = c.0.ty.as_ref() {
-                    for c in &fragment.borrow().0 {
-                        c.try_remove_child(&child);
-                    }
-                }
+    /// Removes the node from its parent.
+    fn detach(&self) {
+        if let Some(parent) = self.parent_node() {
+            if let Some(index) = parent.index_of(self) {
+                parent.children_mut().unwrap().remove(index);
             }
         }
+        *self.0.parent.borrow_mut() = Weak::new();
+    }
 
-        match self.0.ty.as_ref() {
-            SsrNodeType::Element(e) => e.borrow_mut().children.0 = children,
-            SsrNodeType::Fragment(f) => f.borrow_mut().0 = children,
-            _ => panic!("node type cannot have children"),
+    /// Detaches the nodes inserted for `node`, like the DOM, a fragment is replaced by its
+    /// children.
+    fn take_for_insert(&self, node: &Self) -> Vec<SsrNode> {
+        let nodes = match node.0.ty.as_ref() {
+            SsrNodeType::Fragment(f) => mem::take(&mut f.borrow_mut().0),
+            _ => {
+                node.detach();
+                vec![node.clone()]
+            }
         };
+        for node in &nodes {
+            *node.0.parent.borrow_mut() = Rc::downgrade(&self.0);
+        }
+        nodes
     }
 }
 
@@ -107,7 +102,7 @@ impl GenericNode for SsrNode {
     fn element(tag: &str) -> Self {
         SsrNode::new(SsrNodeType::Element(RefCell::new(Element {
             name: tag.to_string(),
-            attributes: HashMap::new(),
+            attributes: Vec::new(),
             children: Default::default(),
         })))
     }
@@ -124,102 +119,86 @@ impl GenericNode for SsrNode {
         SsrNode::new(SsrNodeType::Comment(Default::default()))
     }
 
+    fn doctype(value: &str) -> Self {
+        SsrNode::new(SsrNodeType::Doctype(RefCell::new(Doctype(
+            value.to_string(),
+        ))))
+    }
+
     fn set_attribute(&self, name: &str, value: &str) {
+        let mut element = self.unwrap_element().borrow_mut();
+        // Keep the position of attributes that are set again
+        match element.attributes.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => element
+                .attributes
+                .push((name.to_string(), value.to_string())),
+        }
+    }
+
+    fn remove_attribute(&self, name: &str) {
         self.unwrap_element()
             .borrow_mut()
             .attributes
-            .insert(name.to_string(), value.to_string());
+            .retain(|(key, _)| key != name);
     }
 
-    fn append_child(&self, child: &Self) {
-        child.set_parent(Rc::downgrade(&self.0));
+    fn get_attribute(&self, name: &str) -> Option<String> {
+        SsrNode::get_attribute(self, name)
+    }
 
-        match self.0.ty.as_ref() {
-            SsrNodeType::Element(element) => element.borrow_mut().children.0.push(child.clone()),
-            SsrNodeType::Fragment(fragment) => fragment.borrow_mut().0.push(child.clone()),
-            _ => panic!("node type cannot have children"),
+    fn append_child(&self, child: &Self) {
+        if self.children_mut().is_none() {
+            log::warn!("Could not append child: node type cannot have children");
+            return;
         }
+        let nodes = self.take_for_insert(child);
+        self.children_mut().unwrap().extend(nodes);
     }
 
     fn insert_child_before(&self, new_node: &Self, reference_node: Option<&Self>) {
-        if let Some(reference_node) = reference_node {
-            debug_assert_eq!(
-                reference_node.parent_node().as_ref(),
-                Some(self),
-                "reference node is not a child of this node"
-            );
-        }
-
-        new_node.set_parent(Rc::downgrade(&self.0));
-
-        let mut children = match self.0.ty.as_ref() {
-            SsrNodeType::Element(e) => mem::take(&mut e.borrow_mut().children.0),
-            SsrNodeType::Fragment(f) => mem::take(&mut f.borrow_mut().0),
-            _ => panic!("node type cannot have children"),
+        let reference_node = match reference_node {
+            Some(reference_node) => reference_node,
+            None => return self.append_child(new_node),
         };
-
-        match reference_node {
-            None => self.append_child(new_node),
-            Some(reference) => {
-                children.insert(
-                    children
-                        .iter()
-                        .enumerate()
-                        .find_map(|(i, child)| (child == reference).then(|| i))
-                        .expect("couldn't find reference node"),
-                    new_node.clone(),
-                );
-            }
+        if new_node == reference_node {
+            // Inserting a node before itself leaves it in place
+            return;
         }
-
-        match self.0.ty.as_ref() {
-            SsrNodeType::Element(e) => e.borrow_mut().children.0 = children,
-            SsrNodeType::Fragment(f) => f.borrow_mut().0 = children,
-            _ => panic!("node type cannot have children"),
-        };
+        if self.index_of(reference_node).is_none() {
+            log::warn!("Failed to insert child: reference node is not a child of this node");
+            return;
+        }
+        let nodes = self.take_for_insert(new_node);
+        // The reference moves if the new node was one of its previous siblings
+        let index = self.index_of(reference_node).unwrap();
+        self.children_mut().unwrap().splice(index..index, nodes);
     }
 
     fn remove_child(&self, child: &Self) {
-        let mut children = match self.0.ty.as_ref() {
-            SsrNodeType::Element(e) => mem::take(&mut e.borrow_mut().children.0),
-            SsrNodeType::Fragment(f) => mem::take(&mut f.borrow_mut().0),
-            _ => panic!("node type cannot have children"),
-        };
-
-        let index = children
-            .iter()
-            .enumerate()
-            .find_map(|(i, c)| (c == child).then(|| i))
-            .expect("couldn't find child");
-        children.remove(index);
-
-        match self.0.ty.as_ref() {
-            SsrNodeType::Element(e) => e.borrow_mut().children.0 = children,
-            SsrNodeType::Fragment(f) => f.borrow_mut().0 = children,
-            _ => panic!("node type cannot have children"),
-        };
+        match self.index_of(child) {
+            Some(_) => child.detach(),
+            None => log::warn!("Failed to remove child: node is not a child of this node"),
+        }
     }
 
     fn replace_child(&self, old: &Self, new: &Self) {
-        new.set_parent(Rc::downgrade(&self.0));
-
-        let mut ele = self.unwrap_element().borrow_mut();
-        let children = &mut ele.children.0;
-        let index = children
-            .iter()
-            .enumerate()
-            .find_map(|(i, c)| (c == old).then(|| i))
-            .expect("Couldn't find child");
-        children[index] = new.clone();
+        if old == new {
+            return;
+        }
+        if self.index_of(old).is_none() {
+            log::warn!("Failed to replace child: node is not a child of this node");
+            return;
+        }
+        let nodes = self.take_for_insert(new);
+        let index = self.index_of(old).unwrap();
+        self.children_mut().unwrap().splice(index..=index, nodes);
+        *old.0.parent.borrow_mut() = Weak::new();
     }
 
     fn insert_sibling_before(&self, child: &Self) {
-        child.set_parent(Rc::downgrade(
-            &self.parent_node().expect("no parent for this node").0,
-        ));
-
         self.parent_node()
-            .unwrap()
+            .expect("no parent for this node")
             .insert_child_before(child, Some(self));
     }
 
@@ -228,11 +207,14 @@ impl GenericNode for SsrNode {
     }
 
     fn next_sibling(&self) -> Option<Self> {
-        unimplemented!()
+        let parent = self.parent_node()?;
+        let index = parent.index_of(self)?;
+        let sibling = parent.children_mut()?.get(index + 1).cloned();
+        sibling
     }
 
     fn remove_self(&self) {
-        unimplemented!()
+        self.detach();
     }
 
     // fn event(&self, _name: &str, _handler: Box<EventListener>) {
@@ -240,51 +222,503 @@ impl GenericNode for SsrNode {
     // }
 
     fn update_inner_text(&self, text: &str) {
-        self.unwrap_text().borrow_mut().0 = text.to_string();
+        match self.0.ty.as_ref() {
+            SsrNodeType::Text(t) => t.borrow_mut().0 = text.to_string(),
+            SsrNodeType::Comment(c) => c.borrow_mut().0 = text.to_string(),
+            SsrNodeType::Doctype(_) => {}
+            SsrNodeType::Element(_) | SsrNodeType::Fragment(_) => {
+                self.replace_children_with(&Self::text_node(text))
+            }
+        }
+    }
+
+    fn set_text_content(&self, text: &str) {
+        self.update_inner_text(text);
+    }
+
+    fn set_inner_html(&self, html: &str) {
+        if self.children_mut().is_none() {
+            log::warn!("Could not set inner html: node type cannot have children");
+            return;
+        }
+        match parser::parse(html) {
+            // Appending the parsed fragment moves its nodes
+            Ok(fragment) => self.replace_children_with(&fragment),
+            Err(e) => log::warn!("Could not set inner html: {e}"),
+        }
+    }
+
+    fn replace_children_with(&self, node: &Self) {
+        let children = match self.children_mut() {
+            Some(mut children) => mem::take(&mut *children),
+            None => return,
+        };
+        for child in children {
+            *child.0.parent.borrow_mut() = Weak::new();
+        }
+        self.append_child(node);
+    }
+}
+
+/// The type of an [`SsrNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// An element, eg. `<p>`.
+    Element,
+    /// A text node.
+    Text,
+    /// A comment, also used for markers.
+    Comment,
+    /// A document type declaration.
+    Doctype,
+    /// A fragment, only found at the root of a tree.
+    Fragment,
+}
+
+/// Reading the tree, eg. to assert on the structure of a rendered component in tests.
+///
+/// # Example
+///
+/// ```
+/// use hirola::prelude::*;
+/// use hirola_core::generic_node::ssr_node;
+///
+/// let root = DomType::fragment();
+/// html! {
+///     <ul class="todos">
+///         <li class="done">"Write docs"</li>
+///         <li>"Write tests"</li>
+///     </ul>
+/// }
+/// .mount(&root)
+/// .unwrap();
+///
+/// let root = ssr_node(&root).unwrap();
+/// let items = root.query_selector_all("ul.todos > li").unwrap();
+/// assert_eq!(items.len(), 2);
+/// assert_eq!(items[0].get_attribute("class").as_deref(), Some("done"));
+/// assert_eq!(items[1].text_content(), "Write tests");
+/// assert!(root.find_by_text("Write docs").is_some());
+/// ```
+impl SsrNode {
+    /// Parses `html` into a fragment, eg. to load markup provided by a server.
+    ///
+    /// The parser is forgiving: unknown end tags are ignored and unclosed elements are closed at
+    /// the end of their parent. Optional end tags, eg. of `<p>` and `<li>`, are not inferred.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hirola_core::generic_node::SsrNode;
+    ///
+    /// let html = r#"<ul><li class="done">Write &amp; test</li><li>Ship<br></li></ul>"#;
+    /// let node = SsrNode::parse_html(html).unwrap();
+    /// assert_eq!(node.query_selector_all("li").unwrap().len(), 2);
+    /// assert_eq!(node.to_string(), html);
+    /// ```
+    pub fn parse_html(html: &str) -> Result<SsrNode, ParseError> {
+        parser::parse(html)
+    }
+
+    /// Returns an owned copy of the tree in the stable [`HtmlNode`] format.
+    pub fn to_html_node(&self) -> HtmlNode {
+        HtmlNode::from(self)
+    }
+
+    /// Returns the type of the node.
+    pub fn kind(&self) -> NodeKind {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Element(_) => NodeKind::Element,
+            SsrNodeType::Text(_) => NodeKind::Text,
+            SsrNodeType::Comment(_) => NodeKind::Comment,
+            SsrNodeType::Doctype(_) => NodeKind::Doctype,
+            SsrNodeType::Fragment(_) => NodeKind::Fragment,
+        }
+    }
+
+    /// Returns the tag name of an element, `None` for other nodes.
+    pub fn tag_name(&self) -> Option<String> {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Element(e) => Some(e.borrow().name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the attribute `name` of an element.
+    pub fn get_attribute(&self, name: &str) -> Option<String> {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Element(e) => e
+                .borrow()
+                .attributes
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the attributes of an element in the order they were set.
+    pub fn attributes(&self) -> Vec<(String, String)> {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Element(e) => e.borrow().attributes.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the children of an element or a fragment.
+    pub fn children(&self) -> Vec<SsrNode> {
+        self.children_mut()
+            .map(|children| children.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the text of a text node or a comment.
+    pub fn text(&self) -> Option<String> {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Text(t) => Some(t.borrow().0.clone()),
+            SsrNodeType::Comment(c) => Some(c.borrow().0.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of the node and its descendants, like the DOM `textContent`.
+    pub fn text_content(&self) -> String {
+        match self.0.ty.as_ref() {
+            SsrNodeType::Text(t) => t.borrow().0.clone(),
+            SsrNodeType::Element(_) | SsrNodeType::Fragment(_) => self
+                .children()
+                .iter()
+                .filter(|child| child.kind() != NodeKind::Comment)
+                .map(SsrNode::text_content)
+                .collect(),
+            _ => String::new(),
+        }
+    }
+
+    /// Returns the descendants of the node in document order.
+    pub fn descendants(&self) -> Vec<SsrNode> {
+        let mut descendants = Vec::new();
+        let mut stack = self.children();
+        stack.reverse();
+        while let Some(node) = stack.pop() {
+            stack.extend(node.children().into_iter().rev());
+            descendants.push(node);
+        }
+        descendants
+    }
+
+    /// Returns the first descendant element matching the CSS `selector`.
+    ///
+    /// See [`query_selector_all`](SsrNode::query_selector_all) for the supported selectors.
+    pub fn query_selector(&self, selector: &str) -> Result<Option<SsrNode>, SelectorError> {
+        let selector = Selector::parse(selector)?;
+        Ok(self
+            .descendants()
+            .into_iter()
+            .find(|node| selector.matches(node)))
+    }
+
+    /// Returns the descendant elements matching the CSS `selector` in document order.
+    ///
+    /// Type, universal, id, class and attribute selectors are supported, with the descendant and
+    /// child combinators and selector lists. Pseudo-classes are not.
+    pub fn query_selector_all(&self, selector: &str) -> Result<Vec<SsrNode>, SelectorError> {
+        let selector = Selector::parse(selector)?;
+        Ok(self
+            .descendants()
+            .into_iter()
+            .filter(|node| selector.matches(node))
+            .collect())
+    }
+
+    /// Returns the deepest element whose text content is `text`, ignoring surrounding and repeated
+    /// whitespace.
+    pub fn find_by_text(&self, text: &str) -> Option<SsrNode> {
+        let text = normalize_whitespace(text);
+        self.descendants()
+            .into_iter()
+            .filter(|node| {
+                node.kind() == NodeKind::Element
+                    && normalize_whitespace(&node.text_content()) == text
+            })
+            .last()
     }
 
-    fn replace_children_with(&self, _node: &Self) {
-        unimplemented!()
+    /// Checks if the text content of the node contains `text`, ignoring repeated whitespace.
+    pub fn contains_text(&self, text: &str) -> bool {
+        normalize_whitespace(&self.text_content()).contains(&normalize_whitespace(text))
+    }
+
+    /// Writes the HTML of the node into `out`, one node at a time.
+    pub fn write_html(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        HtmlWriter::new(self, false).write_all(out)
+    }
+
+    /// Writes indented HTML with each element on its own line, for debugging and golden files.
+    ///
+    /// Elements only containing text are kept on one line. The indentation changes the text of the
+    /// document, so the output is not meant to be served.
+    pub fn write_pretty_html(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        HtmlWriter::new(self, true).write_all(out)
     }
 }
 
 impl fmt::Display for SsrNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.0.ty.as_ref() {
-            SsrNodeType::Element(x) => write!(f, "{}", x.borrow()),
-            SsrNodeType::Comment(x) => write!(f, "{}", x.borrow()),
-            SsrNodeType::Text(x) => write!(f, "{}", x.borrow()),
-            SsrNodeType::Fragment(x) => write!(f, "{}", x.borrow()),
+        self.write_html(f)
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A step of the serialization of a tree.
+enum Step {
+    Node {
+        node: SsrNode,
+        depth: usize,
+        /// The node is the text of a raw text element.
+        raw: bool,
+        /// The node is written on the line of its parent.
+        inline: bool,
+    },
+    Close {
+        name: String,
+        depth: usize,
+        inline: bool,
+    },
+}
+
+/// The size of the chunks written by [`HtmlWriter::write_chunk`], in bytes.
+pub(crate) const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Serializes a tree one node at a time, so the output can be flushed between nodes.
+pub(crate) struct HtmlWriter {
+    stack: Vec<Step>,
+    pretty: bool,
+    started: bool,
+}
+
+impl HtmlWriter {
+    pub(crate) fn new(node: &SsrNode, pretty: bool) -> Self {
+        Self {
+            stack: vec![Step::Node {
+                node: node.clone(),
+                depth: 0,
+                raw: false,
+                inline: false,
+            }],
+            pretty,
+            started: false,
         }
     }
+
+    fn push_children(&mut self, children: &[SsrNode], depth: usize, raw: bool, inline: bool) {
+        self.stack
+            .extend(children.iter().rev().map(|node| Step::Node {
+                node: node.clone(),
+                depth,
+                raw,
+                inline,
+            }));
+    }
+
+    /// Starts a new indented line when pretty printing.
+    fn line(&mut self, out: &mut impl fmt::Write, depth: usize, inline: bool) -> fmt::Result {
+        if self.pretty && !inline {
+            if self.started {
+                out.write_char('\n')?;
+            }
+            for _ in 0..depth {
+                out.write_str("  ")?;
+            }
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    /// Writes nodes into `out` until about [`CHUNK_SIZE`] bytes are written, returns `false` once
+    /// the whole tree is written.
+    pub(crate) fn write_chunk(&mut self, out: &mut String) -> Result<bool, fmt::Error> {
+        let start = out.len();
+        while out.len() - start < CHUNK_SIZE {
+            if !self.write_next(out)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Writes the next node into `out`, returns `false` once the whole tree is written.
+    pub(crate) fn write_next(&mut self, out: &mut impl fmt::Write) -> Result<bool, fmt::Error> {
+        let (node, depth, raw, inline) = match self.stack.pop() {
+            None => return Ok(false),
+            Some(Step::Close {
+                name,
+                depth,
+                inline,
+            }) => {
+                self.line(out, depth, inline)?;
+                write!(out, "</{name}>")?;
+                return Ok(true);
+            }
+            Some(Step::Node {
+                node,
+                depth,
+                raw,
+                inline,
+            }) => (node, depth, raw, inline),
+        };
+        match node.0.ty.as_ref() {
+            SsrNodeType::Fragment(fragment) => {
+                let children = fragment.borrow().0.clone();
+                self.push_children(&children, depth, raw, inline);
+            }
+            SsrNodeType::Element(element) => {
+                let element = element.borrow();
+                self.line(out, depth, inline)?;
+                element.write_start_tag(out)?;
+                if !element.is_void() {
+                    // Keep elements only containing text on one line
+                    let inline = inline
+                        || element
+                            .children
+                            .0
+                            .iter()
+                            .all(|child| matches!(child.0.ty.as_ref(), SsrNodeType::Text(_)));
+                    self.stack.push(Step::Close {
+                        name: element.name.clone(),
+                        depth,
+                        inline,
+                    });
+                    self.push_children(
+                        &element.children.0,
+                        depth + 1,
+                        element.is_raw_text(),
+                        inline,
+                    );
+                }
+            }
+            SsrNodeType::Text(text) => {
+                self.line(out, depth, inline)?;
+                let text = text.borrow();
+                if raw {
+                    // Only the closing tag needs escaping, `<\/` is equivalent in scripts and styles
+                    out.write_str(&text.0.replace("</", "<\\/"))?;
+                } else {
+                    write!(out, "{}", text)?;
+                }
+            }
+            SsrNodeType::Comment(comment) => {
+                self.line(out, depth, inline)?;
+                write!(out, "{}", comment.borrow())?;
+            }
+            SsrNodeType::Doctype(doctype) => {
+                self.line(out, depth, inline)?;
+                write!(out, "{}", doctype.borrow())?;
+            }
+        }
+        Ok(true)
+    }
+
+    pub(crate) fn write_all(mut self, out: &mut impl fmt::Write) -> fmt::Result {
+        while self.write_next(out)? {}
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
 pub struct Element {
     name: String,
-    attributes: HashMap<String, String>,
+    /// The attributes in the order they were first set.
+    attributes: Vec<(String, String)>,
     children: Fragment,
 }
 
+/// Elements that cannot have children, written without a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose text is not escaped.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Attributes whose presence means `true`, written without a value.
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+impl Element {
+    fn is_void(&self) -> bool {
+        VOID_ELEMENTS.contains(&self.name.to_ascii_lowercase().as_str())
+    }
+
+    fn is_raw_text(&self) -> bool {
+        RAW_TEXT_ELEMENTS.contains(&self.name.to_ascii_lowercase().as_str())
+    }
+
+    fn write_start_tag(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        write!(out, "<{}", self.name)?;
+        for (name, value) in &self.attributes {
+            if BOOLEAN_ATTRIBUTES.contains(&name.to_ascii_lowercase().as_str()) {
+                write!(out, " {}", name)?;
+            } else {
+                write!(
+                    out,
+                    r#" {}="{}""#,
+                    name,
+                    html_escape::encode_double_quoted_attribute(value)
+                )?;
+            }
+        }
+        write!(out, ">")
+    }
+}
+
 impl fmt::Display for Element {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "<{}", self.name)?;
-        for (name, value) in &self.attributes {
-            write!(
-                f,
-                r#" {}="{}""#,
-                name,
-                html_escape::encode_double_quoted_attribute(value)
-            )?;
+        self.write_start_tag(f)?;
+        if self.is_void() {
+            return Ok(());
         }
-        write!(f, ">{}</{}>", self.children, self.name)?;
-        Ok(())
+        let mut writer = HtmlWriter {
+            stack: Vec::new(),
+            pretty: false,
+            started: false,
+        };
+        writer.push_children(&self.children.0, 0, self.is_raw_text(), false);
+        writer.write_all(f)?;
+        write!(f, "</{}>", self.name)
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
 pub struct Comment(String);
 
 impl fmt::Display for Comment {
@@ -294,7 +728,15 @@ impl fmt::Display for Comment {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
+pub struct Doctype(String);
+
+impl fmt::Display for Doctype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<!DOCTYPE {}>", self.0.replace('>', ""))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct Text(String);
 
 impl fmt::Display for Text {
@@ -304,7 +746,6 @@ impl fmt::Display for Text {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))] 
 pub struct Fragment(Vec<SsrNode>);
 
 impl fmt::Display for Fragment {
@@ -0,0 +1,140 @@
+//! A stable tree format for [`SsrNode`], used to snapshot, diff and round-trip rendered views.
+
+use super::{Comment, Doctype, Element, SsrNode, SsrNodeType, Text};
+use crate::generic_node::GenericNode;
+use std::cell::RefCell;
+
+/// An owned, serializable copy of an [`SsrNode`] tree.
+///
+/// With the `serde` feature, the tree is serialized as tagged objects with the attributes kept in
+/// order as `[name, value]` pairs. This format is stable and is also used to serialize
+/// [`SsrNode`]:
+///
+/// ```json
+/// {
+///   "type": "element",
+///   "name": "a",
+///   "attributes": [["href", "/about"], ["class", "link"]],
+///   "children": [{ "type": "text", "text": "About" }]
+/// }
+/// ```
+///
+/// The other nodes are `{"type": "text", "text": ".."}`, `{"type": "comment", "text": ".."}`,
+/// `{"type": "doctype", "value": "html"}` and `{"type": "fragment", "children": [..]}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "lowercase")
+)]
+pub enum HtmlNode {
+    /// An element with its attributes in order.
+    Element {
+        /// The tag name.
+        name: String,
+        /// The attributes as `(name, value)` pairs.
+        attributes: Vec<(String, String)>,
+        /// The child nodes.
+        children: Vec<HtmlNode>,
+    },
+    /// A text node, unescaped.
+    Text {
+        /// The text.
+        text: String,
+    },
+    /// A comment.
+    Comment {
+        /// The text of the comment.
+        text: String,
+    },
+    /// A document type declaration.
+    Doctype {
+        /// The doctype, eg. `html`.
+        value: String,
+    },
+    /// A list of nodes without a parent element.
+    Fragment {
+        /// The nodes.
+        children: Vec<HtmlNode>,
+    },
+}
+
+impl From<&SsrNode> for HtmlNode {
+    fn from(node: &SsrNode) -> Self {
+        let children = || node.children().iter().map(HtmlNode::from).collect();
+        match node.0.ty.as_ref() {
+            SsrNodeType::Element(element) => {
+                let (name, attributes) = {
+                    let element = element.borrow();
+                    (element.name.clone(), element.attributes.clone())
+                };
+                HtmlNode::Element {
+                    name,
+                    attributes,
+                    children: children(),
+                }
+            }
+            SsrNodeType::Text(text) => HtmlNode::Text {
+                text: text.borrow().0.clone(),
+            },
+            SsrNodeType::Comment(comment) => HtmlNode::Comment {
+                text: comment.borrow().0.clone(),
+            },
+            SsrNodeType::Doctype(doctype) => HtmlNode::Doctype {
+                value: doctype.borrow().0.clone(),
+            },
+            SsrNodeType::Fragment(_) => HtmlNode::Fragment {
+                children: children(),
+            },
+        }
+    }
+}
+
+impl From<&HtmlNode> for SsrNode {
+    fn from(node: &HtmlNode) -> Self {
+        let append = |parent: SsrNode, children: &[HtmlNode]| {
+            for child in children {
+                parent.append_child(&SsrNode::from(child));
+            }
+            parent
+        };
+        match node {
+            HtmlNode::Element {
+                name,
+                attributes,
+                children,
+            } => {
+                let element = SsrNode::new(SsrNodeType::Element(RefCell::new(Element {
+                    name: name.clone(),
+                    attributes: attributes.clone(),
+                    children: Default::default(),
+                })));
+                append(element, children)
+            }
+            HtmlNode::Text { text } => {
+                SsrNode::new(SsrNodeType::Text(RefCell::new(Text(text.clone()))))
+            }
+            HtmlNode::Comment { text } => {
+                SsrNode::new(SsrNodeType::Comment(RefCell::new(Comment(text.clone()))))
+            }
+            HtmlNode::Doctype { value } => {
+                SsrNode::new(SsrNodeType::Doctype(RefCell::new(Doctype(value.clone()))))
+            }
+            HtmlNode::Fragment { children } => append(SsrNode::fragment(), children),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SsrNode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        HtmlNode::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SsrNode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HtmlNode::deserialize(deserializer).map(|node| SsrNode::from(&node))
+    }
+}
@@ -0,0 +1,213 @@
+//! A forgiving HTML parser building [`SsrNode`] trees.
+//!
+//! Unknown end tags are ignored and open elements are closed by the end tag of an ancestor or by
+//! the end of the input. Optional end tags, eg. of `<p>` and `<li>`, are not inferred.
+
+use super::{SsrNode, VOID_ELEMENTS};
+use crate::generic_node::GenericNode;
+use std::{error::Error, fmt};
+
+/// Elements whose content is text up to their end tag.
+const TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// The error returned when HTML cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    message: &'static str,
+    position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ParseError {}
+
+pub(crate) fn parse(html: &str) -> Result<SsrNode, ParseError> {
+    let root = SsrNode::fragment();
+    let mut parser = Parser {
+        html,
+        position: 0,
+        open: vec![(String::new(), root.clone())],
+    };
+    while parser.position < html.len() {
+        parser.next()?;
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    html: &'a str,
+    position: usize,
+    /// The open elements with their lowercase names, starting with the root.
+    open: Vec<(String, SsrNode)>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &'static str) -> ParseError {
+        ParseError {
+            message,
+            position: self.position,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.html[self.position..]
+    }
+
+    fn append(&self, node: &SsrNode) {
+        self.open.last().unwrap().1.append_child(node);
+    }
+
+    /// Parses the next node.
+    fn next(&mut self) -> Result<(), ParseError> {
+        let rest = self.rest();
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment
+                .find("-->")
+                .ok_or_else(|| self.error("unterminated comment"))?;
+            let text = &comment[..end];
+            let comment = SsrNode::marker();
+            comment.update_inner_text(text);
+            self.append(&comment);
+            self.position += 4 + end + 3;
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = rest
+                .find('>')
+                .ok_or_else(|| self.error("unterminated declaration"))?;
+            let declaration = &rest[2..end];
+            if declaration
+                .get(..7)
+                .is_some_and(|name| name.eq_ignore_ascii_case("doctype"))
+            {
+                self.append(&SsrNode::doctype(declaration[7..].trim()));
+            }
+            self.position += end + 1;
+        } else if rest.starts_with("</") {
+            let end = rest
+                .find('>')
+                .ok_or_else(|| self.error("unterminated end tag"))?;
+            let name = rest[2..end].trim().to_ascii_lowercase();
+            // Close the element and its open descendants
+            if let Some(index) = self.open.iter().rposition(|(open, _)| *open == name) {
+                if index > 0 {
+                    self.open.truncate(index);
+                }
+            }
+            self.position += end + 1;
+        } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            self.start_tag()?;
+        } else {
+            // Text runs up to the next tag
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '<')
+                .map_or(rest.len(), |(end, _)| end);
+            let text = html_escape::decode_html_entities(&rest[..end]);
+            self.append(&SsrNode::text_node(&text));
+            self.position += end;
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.position = self.html.len() - trimmed.len();
+    }
+
+    /// Reads up to the first character matching `end`.
+    fn read_until(&mut self, end: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        let length = self.rest().find(end).unwrap_or(self.rest().len());
+        self.position += length;
+        &self.html[start..self.position]
+    }
+
+    fn start_tag(&mut self) -> Result<(), ParseError> {
+        self.position += 1;
+        let name = self
+            .read_until(|c| c.is_whitespace() || c == '/' || c == '>')
+            .to_ascii_lowercase();
+        let element = SsrNode::element(&name);
+        let self_closing = loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error("unterminated tag"));
+            } else if rest.starts_with("/>") {
+                self.position += 2;
+                break true;
+            } else if rest.starts_with('>') {
+                self.position += 1;
+                break false;
+            } else if rest.starts_with('/') {
+                self.position += 1;
+                continue;
+            }
+            let attribute = self
+                .read_until(|c| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+                .to_ascii_lowercase();
+            self.skip_whitespace();
+            let value = if self.rest().starts_with('=') {
+                self.position += 1;
+                self.skip_whitespace();
+                self.attribute_value()?
+            } else {
+                String::new()
+            };
+            // The first of duplicated attributes wins
+            if element.get_attribute(&attribute).is_none() {
+                element.set_attribute(&attribute, &value);
+            }
+        };
+        self.append(&element);
+        if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+            return Ok(());
+        }
+        if TEXT_ELEMENTS.contains(&name.as_str()) {
+            let end_tag = format!("</{name}");
+            let length = self
+                .rest()
+                .to_ascii_lowercase()
+                .find(&end_tag)
+                .unwrap_or(self.rest().len());
+            let text = &self.rest()[..length];
+            if !text.is_empty() {
+                let text = match name.as_str() {
+                    "script" | "style" => text.into(),
+                    _ => html_escape::decode_html_entities(text),
+                };
+                element.append_child(&SsrNode::text_node(&text));
+            }
+            self.position += length;
+            // Skip the end tag
+            if let Some(end) = self.rest().find('>') {
+                self.position += end + 1;
+            }
+            return Ok(());
+        }
+        self.open.push((name, element));
+        Ok(())
+    }
+
+    fn attribute_value(&mut self) -> Result<String, ParseError> {
+        let value = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                self.position += 1;
+                let length = self
+                    .rest()
+                    .find(quote)
+                    .ok_or_else(|| self.error("unterminated attribute value"))?;
+                let value = &self.rest()[..length];
+                self.position += length + 1;
+                value
+            }
+            _ => self.read_until(|c| c.is_whitespace() || c == '>'),
+        };
+        Ok(html_escape::decode_html_entities(value).into_owned())
+    }
+}
@@ -0,0 +1,350 @@
+//! A subset of CSS selectors used to query [`SsrNode`] trees.
+//!
+//! Supported are type (`p`), universal (`*`), id (`#main`), class (`.item`) and attribute
+//! (`[href]`, `[type=text]`, `[class~=a]`, `[href^=/]`, `[href$=.png]`, `[href*=x]`, `[lang|=en]`)
+//! selectors, compounds of them, the descendant (` `) and child (`>`) combinators and selector
+//! lists (`,`).
+
+use super::{NodeKind, SsrNode};
+use crate::generic_node::GenericNode;
+use std::{error::Error, fmt};
+
+/// The error returned when a selector cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorError {
+    selector: String,
+    position: usize,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid selector `{}` at position {}",
+            self.selector, self.position
+        )
+    }
+}
+
+impl Error for SelectorError {}
+
+/// A parsed selector list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Selector(Vec<Complex>);
+
+/// Compounds joined by combinators, `combinators[i]` joins `compounds[i]` and `compounds[i + 1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Complex {
+    compounds: Vec<Compound>,
+    combinators: Vec<Combinator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<AttributeSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AttributeSelector {
+    name: String,
+    matcher: Option<(Operator, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Equals,
+    Includes,
+    DashMatch,
+    Prefix,
+    Suffix,
+    Substring,
+}
+
+impl Selector {
+    pub(crate) fn parse(selector: &str) -> Result<Self, SelectorError> {
+        let mut parser = Parser {
+            selector,
+            position: 0,
+        };
+        let mut list = vec![parser.complex()?];
+        while parser.eat(',') {
+            list.push(parser.complex()?);
+        }
+        if parser.position < selector.len() {
+            return Err(parser.error());
+        }
+        Ok(Self(list))
+    }
+
+    pub(crate) fn matches(&self, node: &SsrNode) -> bool {
+        self.0
+            .iter()
+            .any(|complex| complex.matches(node, complex.compounds.len() - 1))
+    }
+}
+
+impl Complex {
+    /// Checks if `node` matches the compounds up to `index`.
+    fn matches(&self, node: &SsrNode, index: usize) -> bool {
+        if !self.compounds[index].matches(node) {
+            return false;
+        }
+        if index == 0 {
+            return true;
+        }
+        let mut ancestor = parent_element(node);
+        match self.combinators[index - 1] {
+            Combinator::Child => ancestor.is_some_and(|parent| self.matches(&parent, index - 1)),
+            Combinator::Descendant => {
+                while let Some(node) = ancestor {
+                    if self.matches(&node, index - 1) {
+                        return true;
+                    }
+                    ancestor = parent_element(&node);
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Returns the closest ancestor that is an element, skipping fragments.
+fn parent_element(node: &SsrNode) -> Option<SsrNode> {
+    let mut parent = node.parent_node();
+    while let Some(node) = parent {
+        if node.kind() == NodeKind::Element {
+            return Some(node);
+        }
+        parent = node.parent_node();
+    }
+    None
+}
+
+impl Compound {
+    fn matches(&self, node: &SsrNode) -> bool {
+        let tag = match node.tag_name() {
+            Some(tag) => tag,
+            None => return false,
+        };
+        if let Some(expected) = &self.tag {
+            if !expected.eq_ignore_ascii_case(&tag) {
+                return false;
+            }
+        }
+        if let Some(id) = &self.id {
+            if node.get_attribute("id").as_ref() != Some(id) {
+                return false;
+            }
+        }
+        if !self.classes.is_empty() {
+            let classes = node.get_attribute("class").unwrap_or_default();
+            if !self
+                .classes
+                .iter()
+                .all(|class| classes.split_whitespace().any(|c| c == class))
+            {
+                return false;
+            }
+        }
+        self.attributes.iter().all(|attribute| {
+            let value = match node.get_attribute(&attribute.name) {
+                Some(value) => value,
+                None => return false,
+            };
+            match &attribute.matcher {
+                None => true,
+                Some((Operator::Equals, expected)) => &value == expected,
+                Some((Operator::Includes, expected)) => {
+                    value.split_whitespace().any(|word| word == expected)
+                }
+                Some((Operator::DashMatch, expected)) => {
+                    &value == expected || value.starts_with(&format!("{expected}-"))
+                }
+                Some((Operator::Prefix, expected)) => {
+                    !expected.is_empty() && value.starts_with(expected.as_str())
+                }
+                Some((Operator::Suffix, expected)) => {
+                    !expected.is_empty() && value.ends_with(expected.as_str())
+                }
+                Some((Operator::Substring, expected)) => {
+                    !expected.is_empty() && value.contains(expected.as_str())
+                }
+            }
+        })
+    }
+}
+
+struct Parser<'a> {
+    selector: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> SelectorError {
+        SelectorError {
+            selector: self.selector.to_string(),
+            position: self.position,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.selector[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.position;
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+        self.position > start
+    }
+
+    /// Consumes `expected` and the whitespace around it.
+    fn eat(&mut self, expected: char) -> bool {
+        let start = self.position;
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            self.skip_whitespace();
+            true
+        } else {
+            self.position = start;
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, SelectorError> {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                self.position += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        if self.position == start {
+            return Err(self.error());
+        }
+        Ok(self.selector[start..self.position].to_string())
+    }
+
+    fn complex(&mut self) -> Result<Complex, SelectorError> {
+        self.skip_whitespace();
+        let mut complex = Complex {
+            compounds: vec![self.compound()?],
+            combinators: Vec::new(),
+        };
+        loop {
+            let combinator = if self.eat('>') {
+                Combinator::Child
+            } else if self.skip_whitespace() {
+                match self.peek() {
+                    None | Some(',') => break,
+                    Some(_) => Combinator::Descendant,
+                }
+            } else {
+                break;
+            };
+            complex.combinators.push(combinator);
+            complex.compounds.push(self.compound()?);
+        }
+        Ok(complex)
+    }
+
+    fn compound(&mut self) -> Result<Compound, SelectorError> {
+        let start = self.position;
+        let mut compound = Compound::default();
+        match self.peek() {
+            Some('*') => self.position += 1,
+            Some(c) if c.is_alphanumeric() => compound.tag = Some(self.ident()?),
+            _ => {}
+        }
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.position += 1;
+                    compound.id = Some(self.ident()?);
+                }
+                Some('.') => {
+                    self.position += 1;
+                    compound.classes.push(self.ident()?);
+                }
+                Some('[') => {
+                    self.position += 1;
+                    compound.attributes.push(self.attribute()?);
+                }
+                _ => break,
+            }
+        }
+        if self.position == start {
+            return Err(self.error());
+        }
+        Ok(compound)
+    }
+
+    fn attribute(&mut self) -> Result<AttributeSelector, SelectorError> {
+        self.skip_whitespace();
+        let name = self.ident()?;
+        self.skip_whitespace();
+        let operator = match self.peek() {
+            Some(']') => {
+                self.position += 1;
+                return Ok(AttributeSelector {
+                    name,
+                    matcher: None,
+                });
+            }
+            Some('=') => Operator::Equals,
+            Some('~') => Operator::Includes,
+            Some('|') => Operator::DashMatch,
+            Some('^') => Operator::Prefix,
+            Some('$') => Operator::Suffix,
+            Some('*') => Operator::Substring,
+            _ => return Err(self.error()),
+        };
+        if operator != Operator::Equals {
+            self.position += 1;
+        }
+        if self.peek() != Some('=') {
+            return Err(self.error());
+        }
+        self.position += 1;
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.position += 1;
+                let length = self.selector[self.position..]
+                    .find(quote)
+                    .ok_or_else(|| self.error())?;
+                let value = self.selector[self.position..self.position + length].to_string();
+                self.position += length + 1;
+                value
+            }
+            _ => {
+                let start = self.position;
+                while let Some(c) = self.peek().filter(|c| *c != ']' && !c.is_whitespace()) {
+                    self.position += c.len_utf8();
+                }
+                self.selector[start..self.position].to_string()
+            }
+        };
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            return Err(self.error());
+        }
+        self.position += 1;
+        Ok(AttributeSelector {
+            name,
+            matcher: Some((operator, value)),
+        })
+    }
+}
@@ -0,0 +1,335 @@
+//! Document head management.
+//!
+//! The [`Title`], [`Meta`] and [`HeadLink`] components set entries of the document `<head>` from
+//! anywhere in a template. Entries are deduplicated by key: the title, the meta `name` (or
+//! `property`), and the link `rel` and `href` (only the `rel` for canonical links). When several
+//! components set the same entry, the last one rendered wins, so nested pages override their
+//! layouts.
+//!
+//! With the `dom` feature, entries are applied to `document.head` and removed once the component
+//! is discarded, unless another component took them over. With the `ssr` feature, entries are
+//! collected with [`collect`] instead of being rendered in place.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use hirola::prelude::*;
+//!
+//! fn about_page() -> Dom {
+//!     html! {
+//!         <main>
+//!             <Title text="About us" />
+//!             <Meta name="description" content="Who we are" />
+//!             <Meta name="og:title" content="About us" />
+//!             <HeadLink rel="canonical" href="https://example.com/about" />
+//!             <h1>"About us"</h1>
+//!         </main>
+//!     }
+//! }
+//! ```
+use crate::{
+    dom::Dom,
+    render::{Error, Render},
+};
+#[cfg(feature = "ssr")]
+use std::cell::RefCell;
+
+/// The attribute marking the head elements managed by hirola, holding the entry key.
+pub const HEAD_KEY_ATTRIBUTE: &str = "data-hirola-head";
+
+/// Sets the document title.
+#[derive(Debug, Clone)]
+pub struct Title<T: AsRef<str>> {
+    /// The title text.
+    pub text: T,
+}
+
+/// Adds a `<meta>` tag to the document head.
+///
+/// Names containing a `:`, eg. `og:title`, are rendered as a `property` for OpenGraph.
+#[derive(Debug, Clone)]
+pub struct Meta<N: AsRef<str>, C: AsRef<str>> {
+    /// The meta name, eg. `description`.
+    pub name: N,
+    /// The meta content.
+    pub content: C,
+}
+
+/// Adds a `<link>` tag to the document head, eg. a canonical URL or a stylesheet.
+#[derive(Debug, Clone)]
+pub struct HeadLink<R: AsRef<str>, H: AsRef<str>> {
+    /// The link relation, eg. `canonical`.
+    pub rel: R,
+    /// The linked URL.
+    pub href: H,
+}
+
+/// An entry of the document head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HeadEntry {
+    key: String,
+    tag: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    text: Option<String>,
+}
+
+impl HeadEntry {
+    fn title(text: &str) -> Self {
+        Self {
+            key: String::from("title"),
+            tag: "title",
+            attributes: Vec::new(),
+            text: Some(text.to_string()),
+        }
+    }
+
+    fn meta(name: &str, content: &str) -> Self {
+        let kind = if name.contains(':') {
+            "property"
+        } else {
+            "name"
+        };
+        Self {
+            key: format!("meta:{name}"),
+            tag: "meta",
+            attributes: vec![(kind, name.to_string()), ("content", content.to_string())],
+            text: None,
+        }
+    }
+
+    fn link(rel: &str, href: &str) -> Self {
+        let key = match rel {
+            "canonical" => format!("link:{rel}"),
+            _ => format!("link:{rel}:{href}"),
+        };
+        Self {
+            key,
+            tag: "link",
+            attributes: vec![("rel", rel.to_string()), ("href", href.to_string())],
+            text: None,
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    fn to_html(&self) -> String {
+        let mut html = format!(
+            r#"<{} {HEAD_KEY_ATTRIBUTE}="{}""#,
+            self.tag,
+            html_escape::encode_double_quoted_attribute(&self.key)
+        );
+        for (name, value) in &self.attributes {
+            html.push_str(&format!(
+                r#" {name}="{}""#,
+                html_escape::encode_double_quoted_attribute(value)
+            ));
+        }
+        html.push('>');
+        if let Some(text) = &self.text {
+            html.push_str(&html_escape::encode_text(text));
+            html.push_str(&format!("</{}>", self.tag));
+        }
+        html
+    }
+
+    /// Applies the entry to `document.head` until `parent` is discarded.
+    ///
+    /// Components applying the same key are stacked: the last one is shown, and discarding it shows
+    /// the previous one again, eg. the title of a layout once a nested page is left.
+    #[cfg(feature = "dom")]
+    fn apply(self, parent: &Dom) {
+        use std::cell::{Cell, RefCell};
+        use std::collections::HashMap;
+        thread_local! {
+            static NEXT_OWNER: Cell<u64> = const { Cell::new(0) };
+            // The entries applied for each key by the mounted components, the last one is shown
+            static STACKS: RefCell<HashMap<String, Vec<(u64, HeadEntry)>>> =
+                RefCell::new(HashMap::new());
+        }
+        self.write();
+        let owner = NEXT_OWNER.with(|next| next.replace(next.get() + 1));
+        STACKS.with(|stacks| {
+            stacks
+                .borrow_mut()
+                .entry(self.key.clone())
+                .or_default()
+                .push((owner, self.clone()))
+        });
+        parent.on_cleanup(move || {
+            let (shown, previous) = STACKS.with(|stacks| {
+                let mut stacks = stacks.borrow_mut();
+                let Some(stack) = stacks.get_mut(&self.key) else {
+                    return (false, None);
+                };
+                let Some(index) = stack.iter().position(|(o, _)| *o == owner) else {
+                    return (false, None);
+                };
+                stack.remove(index);
+                let shown = index == stack.len();
+                let previous = stack.last().map(|(_, entry)| entry.clone());
+                if stack.is_empty() {
+                    stacks.remove(&self.key);
+                }
+                (shown, previous)
+            });
+            if !shown {
+                return;
+            }
+            match previous {
+                Some(previous) => previous.write(),
+                // The title is kept until another page sets it
+                None if self.key != "title" => {
+                    if let Some(element) = self.find() {
+                        element.remove();
+                    }
+                }
+                None => {}
+            }
+        });
+    }
+
+    /// Returns the element of the entry in `document.head`.
+    #[cfg(feature = "dom")]
+    fn find(&self) -> Option<web_sys::Element> {
+        let head = web_sys::window()?.document()?.head()?;
+        let selector = match self.tag {
+            // Reuse the title of the page shell
+            "title" => String::from("title"),
+            tag => format!(
+                r#"{tag}[{HEAD_KEY_ATTRIBUTE}="{}"]"#,
+                self.key.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+        };
+        head.query_selector(&selector).ok().flatten()
+    }
+
+    /// Writes the entry to its element in `document.head`, creating it if needed.
+    #[cfg(feature = "dom")]
+    fn write(&self) {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let head = match document.head() {
+            Some(head) => head,
+            None => return,
+        };
+        let element = match self.find() {
+            Some(element) => element,
+            None => {
+                let element = document.create_element(self.tag).unwrap();
+                head.append_child(&element).unwrap();
+                element
+            }
+        };
+        element
+            .set_attribute(HEAD_KEY_ATTRIBUTE, &self.key)
+            .unwrap();
+        for (name, value) in &self.attributes {
+            element.set_attribute(name, value).unwrap();
+        }
+        if let Some(text) = &self.text {
+            element.set_text_content(Some(text));
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+thread_local! {
+    static COLLECTED: RefCell<Option<Vec<HeadEntry>>> = const { RefCell::new(None) };
+}
+
+/// Head entries collected while rendering on the server.
+#[cfg(feature = "ssr")]
+#[derive(Debug)]
+pub(crate) struct HeadCollector {
+    previous: Option<Vec<HeadEntry>>,
+}
+
+#[cfg(feature = "ssr")]
+impl HeadCollector {
+    /// Starts collecting the head entries rendered on this thread.
+    pub(crate) fn start() -> Self {
+        let previous = COLLECTED.with(|collected| collected.replace(Some(Vec::new())));
+        Self { previous }
+    }
+
+    /// Stops collecting and returns the collected entries as HTML.
+    pub(crate) fn finish(self) -> String {
+        let entries = COLLECTED
+            .with(|collected| collected.replace(self.previous))
+            .unwrap_or_default();
+        entries.iter().map(HeadEntry::to_html).collect()
+    }
+}
+
+/// Runs `render`, collecting the head entries rendered meanwhile as an HTML string.
+///
+/// [`App::render_response`](crate::app::App::render_response) already collects the head entries of
+/// the rendered page.
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+///
+/// # Example
+///
+/// ```
+/// use hirola::prelude::*;
+///
+/// let (body, head) = hirola::head::collect(|| {
+///     render_to_string(html! {
+///         <main>
+///             <Title text="Home" />
+///             <h1>"Home"</h1>
+///         </main>
+///     })
+/// });
+/// assert_eq!(body, "<main><h1>Home</h1></main>");
+/// assert_eq!(head, r#"<title data-hirola-head="title">Home</title>"#);
+/// ```
+#[cfg(feature = "ssr")]
+pub fn collect<R>(render: impl FnOnce() -> R) -> (R, String) {
+    let collector = HeadCollector::start();
+    let result = render();
+    (result, collector.finish())
+}
+
+fn render_entry(entry: HeadEntry, parent: &Dom) -> Result<(), Error> {
+    #[cfg(feature = "dom")]
+    if crate::generic_node::backend() == crate::generic_node::Backend::Dom {
+        entry.apply(parent);
+        return Ok(());
+    }
+    #[cfg(feature = "ssr")]
+    {
+        let _ = parent;
+        COLLECTED.with(|collected| {
+            if let Some(entries) = collected.borrow_mut().as_mut() {
+                match entries.iter().position(|e| e.key == entry.key) {
+                    Some(index) => entries[index] = entry,
+                    None => entries.push(entry),
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+impl<T: AsRef<str>> Render for Title<T> {
+    fn render_into(self: Box<Self>, parent: &Dom) -> Result<(), Error> {
+        render_entry(HeadEntry::title(self.text.as_ref()), parent)
+    }
+}
+
+impl<N: AsRef<str>, C: AsRef<str>> Render for Meta<N, C> {
+    fn render_into(self: Box<Self>, parent: &Dom) -> Result<(), Error> {
+        render_entry(
+            HeadEntry::meta(self.name.as_ref(), self.content.as_ref()),
+            parent,
+        )
+    }
+}
+
+impl<R: AsRef<str>, H: AsRef<str>> Render for HeadLink<R, H> {
+    fn render_into(self: Box<Self>, parent: &Dom) -> Result<(), Error> {
+        render_entry(
+            HeadEntry::link(self.rel.as_ref(), self.href.as_ref()),
+            parent,
+        )
+    }
+}
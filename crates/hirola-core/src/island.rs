@@ -0,0 +1,128 @@
+//! Islands: interactive components hydrated on their own.
+//!
+//! Components marked with [`#[island]`](hirola_macros::island) render inside a `<hirola-island>`
+//! element holding their name and serialized props. On the client, [`hydrate`] renders each island
+//! again into its element, leaving the rest of the server HTML untouched. Pages that are mostly
+//! static only run the code of their islands.
+//!
+//! Hydrating does not reuse the server markup of an island: its nodes are removed and rendered
+//! again from the props. Anything the user changed before hydration, such as the value of an input
+//! or the focus, is lost.
+//!
+//! Pages rendered by an app can also be hydrated as a whole with `App::hydrate`, which replaces the
+//! server markup, islands included, with the client render.
+//!
+//! Props are serialized with `serde`, so they must own their data, eg. `String` instead of `&str`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use hirola::prelude::*;
+//!
+//! #[island]
+//! #[component]
+//! fn Counter(initial: i32) -> Dom {
+//!     let count = Mutable::new(initial);
+//!     let increment = count.callback(|s| *s.lock_mut() += 1);
+//!     html! {
+//!         <button on:click=increment>{count}</button>
+//!     }
+//! }
+//!
+//! fn page() -> Dom {
+//!     html! {
+//!         <main>
+//!             <h1>"A static page"</h1>
+//!             <Counter initial=3 />
+//!         </main>
+//!     }
+//! }
+//! ```
+//!
+//! The server renders `page` as usual, and the client only hydrates the counter:
+//!
+//! ```ignore
+//! let islands = hirola::prelude::island::hydrate::<Counter>().unwrap();
+//! std::mem::forget(islands);
+//! ```
+//!
+//! _This API requires the following crate features to be activated: `serde`_
+
+use crate::{dom::Dom, render::Error};
+use serde::{de::DeserializeOwned, Serialize};
+
+#[doc(hidden)]
+pub use serde;
+
+/// The tag of the elements wrapping islands.
+pub const ISLAND_TAG: &str = "hirola-island";
+
+/// The attribute holding the name of an island.
+pub const ISLAND_NAME_ATTRIBUTE: &str = "data-island";
+
+/// The attribute holding the serialized props of an island.
+pub const ISLAND_PROPS_ATTRIBUTE: &str = "data-props";
+
+/// A component hydrated on its own, implemented by [`#[island]`](hirola_macros::island).
+pub trait Island: Serialize + DeserializeOwned + 'static {
+    /// The name identifying the island in the rendered HTML.
+    const NAME: &'static str;
+
+    /// Renders the component into `parent`, without the island element.
+    fn render_island(self: Box<Self>, parent: &Dom) -> Result<(), Error>;
+}
+
+/// Renders `island` inside an island element holding its name and props.
+#[doc(hidden)]
+pub fn render_island<I: Island>(island: Box<I>, parent: &Dom) -> Result<(), Error> {
+    let props = serde_json::to_string(&island).map_err(|e| Error::DomError(Box::new(e)))?;
+    let element = Dom::element(ISLAND_TAG);
+    element.attribute(ISLAND_NAME_ATTRIBUTE, I::NAME);
+    element.attribute(ISLAND_PROPS_ATTRIBUTE, &props);
+    // Keep the layout of the page as if the component was rendered in place
+    element.attribute("style", "display: contents");
+    island.render_island(&element)?;
+    parent.append_child(element)
+}
+
+/// Hydrates the islands of type `I` rendered on the server.
+///
+/// The server markup inside each island element is cleared and the component is rendered again
+/// from its props, the existing nodes are not reused. Islands with invalid props are skipped with a
+/// warning. The returned doms have to be kept alive
+/// for the islands to stay interactive, eg. with [`std::mem::forget`].
+///
+/// _This API requires the following crate features to be activated: `dom`, `serde`_
+#[cfg(feature = "dom")]
+pub fn hydrate<I: Island>() -> Result<Vec<Dom>, Error> {
+    use crate::generic_node::{from_dom, DomNode, GenericNode};
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return Ok(Vec::new());
+    };
+    let selector = format!("{ISLAND_TAG}[{ISLAND_NAME_ATTRIBUTE}=\"{}\"]", I::NAME);
+    let elements = document
+        .query_selector_all(&selector)
+        .map_err(|e| Error::DomError(Box::new(e)))?;
+    let mut islands = Vec::new();
+    for index in 0..elements.length() {
+        let Some(node) = elements.item(index) else {
+            continue;
+        };
+        let props = wasm_bindgen::JsCast::dyn_ref::<web_sys::Element>(&node)
+            .and_then(|element| element.get_attribute(ISLAND_PROPS_ATTRIBUTE))
+            .unwrap_or_default();
+        let island: I = match serde_json::from_str(&props) {
+            Ok(island) => island,
+            Err(e) => {
+                log::warn!("Skipping the island {} with invalid props: {e}", I::NAME);
+                continue;
+            }
+        };
+        let root = Dom::new_from_node(&from_dom(DomNode { node }));
+        root.node().set_text_content("");
+        Box::new(island).render_island(&root)?;
+        islands.push(root);
+    }
+    Ok(islands)
+}
@@ -27,6 +27,9 @@
 //! - `dom` (_default_) - Enables rendering templates to DOM nodes. Only useful on `wasm32-unknown-unknown` target.
 //! - `ssr` - Enables rendering templates to static strings (useful for Server Side Rendering / Server side Generation).
 //! - `serde` - Enables serializing and deserializing `Signal`s and other wrapper types using `serde`.
+//!
+//! `dom` and `ssr` can be activated together, eg. to pre-render pages and hydrate them from the same
+//! crate. The backend is then selected at runtime, see [`generic_node::backend`].
 
 #![allow(non_snake_case)]
 #![warn(clippy::clone_on_ref_ptr)]
@@ -43,11 +46,15 @@ pub type BoxedLocal<T> = Pin<Box<dyn Future<Output = T> + 'static>>;
 
 #[cfg(feature = "app")]
 pub mod app;
-#[cfg(feature = "dom")]
 pub mod callback;
+#[cfg(feature = "ssr")]
+pub mod document;
 pub mod dom;
 pub mod effect;
 pub mod generic_node;
+pub mod head;
+#[cfg(feature = "serde")]
+pub mod island;
 pub mod mixins;
 pub mod render;
 pub mod templating;
@@ -73,9 +80,9 @@ pub fn render(dom: Dom) -> Result<Dom, render::Error> {
 /// _This API requires the following crate features to be activated: `dom`_
 #[cfg(feature = "dom")]
 pub fn render_to(dom: dom::Dom, parent: &web_sys::Node) -> Result<dom::Dom, render::Error> {
-    dom.mount(&DomNode {
+    dom.mount(&generic_node::from_dom(DomNode {
         node: parent.clone(),
-    })
+    }))
 }
 
 /// Render a [`Dom`] into a static [`String`]. Useful for rendering to a string on the server side.
@@ -83,13 +90,94 @@ pub fn render_to(dom: dom::Dom, parent: &web_sys::Node) -> Result<dom::Dom, rend
 /// _This API requires the following crate features to be activated: `ssr`_
 #[cfg(feature = "ssr")]
 pub fn render_to_string(dom: Dom) -> String {
+    let (_root, node) = render_to_root(dom);
+    format!("{node}")
+}
+
+/// Render a [`Dom`] into an indented [`String`], with each element on its own line.
+/// Useful for debugging and golden files.
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+#[cfg(feature = "ssr")]
+pub fn render_to_string_pretty(dom: Dom) -> String {
+    let (_root, node) = render_to_root(dom);
+    let mut html = String::new();
+    node.write_pretty_html(&mut html).unwrap();
+    html
+}
+
+/// Render a [`Dom`] into an [`io::Write`](std::io::Write) sink, writing the HTML as the tree is
+/// serialized instead of building a [`String`].
+///
+/// The HTML is written in many small pieces, so wrap unbuffered writers in a
+/// [`BufWriter`](std::io::BufWriter).
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+#[cfg(feature = "ssr")]
+pub fn render_to_writer<W: std::io::Write>(dom: Dom, writer: &mut W) -> std::io::Result<()> {
+    struct IoWriter<'a, W> {
+        inner: &'a mut W,
+        error: Option<std::io::Error>,
+    }
+    impl<W: std::io::Write> std::fmt::Write for IoWriter<'_, W> {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.inner.write_all(s.as_bytes()).map_err(|e| {
+                self.error = Some(e);
+                std::fmt::Error
+            })
+        }
+    }
+    let (_root, node) = render_to_root(dom);
+    let mut writer = IoWriter {
+        inner: writer,
+        error: None,
+    };
+    match node.write_html(&mut writer) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(writer
+            .error
+            .unwrap_or_else(|| std::io::Error::other("failed to serialize the node"))),
+    }
+}
+
+/// Render a [`Dom`] into an [`AsyncWrite`](futures_util::io::AsyncWrite) sink.
+///
+/// The HTML is written in chunks of about 8KiB as the tree is serialized.
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+#[cfg(feature = "ssr")]
+pub async fn render_to_async_writer<W>(dom: Dom, writer: &mut W) -> std::io::Result<()>
+where
+    W: futures_util::io::AsyncWrite + Unpin,
+{
+    use futures_util::io::AsyncWriteExt;
+    use generic_node::ssr_node::{HtmlWriter, CHUNK_SIZE};
+    let (_root, node) = render_to_root(dom);
+    let mut html = HtmlWriter::new(&node, false);
+    let mut chunk = String::with_capacity(CHUNK_SIZE);
+    loop {
+        let more = html.write_chunk(&mut chunk).map_err(std::io::Error::other)?;
+        writer.write_all(chunk.as_bytes()).await?;
+        chunk.clear();
+        if !more {
+            break;
+        }
+    }
+    writer.flush().await
+}
+
+/// Renders `dom` into a detached fragment, returning the [`Dom`] to keep alive while the fragment
+/// is serialized.
+#[cfg(feature = "ssr")]
+fn render_to_root(dom: Dom) -> (Dom, generic_node::SsrNode) {
     use crate::generic_node::GenericNode;
     use crate::generic_node::SsrNode;
     use crate::render::Render;
+    let _scope = generic_node::SsrScope::enter();
     let node = SsrNode::fragment();
-    let root = Dom::new_from_node(&node);
+    let root = Dom::new_from_node(&generic_node::from_ssr(node.clone()));
     Render::render_into(Box::new(dom), &root).unwrap();
-    format!("{}", root.node())
+    (root, node)
 }
 
 #[inline]
@@ -100,9 +188,11 @@ where
     let (handle, future) = cancelable_future(future, || ());
 
     #[cfg(feature = "dom")]
-    wasm_bindgen_futures::spawn_local(future);
+    if generic_node::backend() == generic_node::Backend::Dom {
+        wasm_bindgen_futures::spawn_local(future);
+        return handle;
+    }
 
-    #[cfg(not(feature = "dom"))]
     drop(future);
     // tokio::task::spawn_local(future);
 
@@ -113,23 +203,26 @@ pub mod prelude {
 
     // pub use crate::spawn;
     pub use crate::effect::SideEffect;
-    #[cfg(feature = "dom")]
-    pub use crate::generic_node::DomNode as DomType;
-    pub use crate::generic_node::GenericNode;
-    #[cfg(feature = "ssr")]
-    pub use crate::generic_node::SsrNode as DomType;
+    pub use crate::generic_node::{DomType, Event, GenericNode};
+    pub use crate::head::{HeadLink, Meta, Title};
     pub use crate::templating::flow::{Indexed, IndexedProps};
     pub use crate::templating::noderef::NodeRef;
     pub use crate::templating::suspense::{Suspend, Suspense, SuspenseResult::*};
     pub use crate::templating::switch::Switch;
     pub use futures_signals::*;
     pub use hirola_macros::{component, html};
+    #[cfg(feature = "serde")]
+    pub use crate::island::{self, Island};
+    #[cfg(feature = "serde")]
+    pub use hirola_macros::island;
 
-    #[cfg(feature = "dom")]
     pub use crate::callback::Callback;
     pub use crate::dom::Dom;
     #[cfg(feature = "ssr")]
-    pub use crate::render_to_string;
+    pub use crate::{
+        document::render_document, render_to_async_writer, render_to_string,
+        render_to_string_pretty, render_to_writer,
+    };
     #[cfg(feature = "dom")]
     pub use crate::{render, render_to};
 
@@ -3,19 +3,22 @@
 //! Here is an example of a mixin
 //! ```rust,no_run
 //! use hirola::prelude::*;
-//! use web_sys::Element;
+//! use futures_signals::signal::SignalExt;
 //! // Mixin that controls tailwind opacity based on a bool signal
 //! fn opacity<'a>(signal: &'a Mutable<bool>) -> Box<dyn Fn(&Dom) -> () + 'a> {
 //!    let cb = move |dom: &Dom| {
 //!        let node = dom.node().clone();
-//!        let element = node.unchecked_into::<Element>();
-//!        if signal.get() {
-//!            element.class_list().add_1("opacity-100").unwrap();
-//!            element.class_list().remove_1("opacity-0").unwrap();
-//!        } else {
-//!            element.class_list().add_1("opacity-0").unwrap();
-//!            element.class_list().remove_1("opacity-100").unwrap();
-//!        }
+//!        dom.effect(signal.signal().for_each(move |shown| {
+//!            // Only swap the opacity class, keeping the other classes of the element
+//!            let class = node.get_attribute("class").unwrap_or_default();
+//!            let mut classes: Vec<&str> = class
+//!                .split_whitespace()
+//!                .filter(|class| !class.starts_with("opacity-"))
+//!                .collect();
+//!            classes.push(if shown { "opacity-100" } else { "opacity-0" });
+//!            node.set_attribute("class", &classes.join(" "));
+//!            async {}
+//!        }));
 //!    };
 //!    Box::new(cb)
 //! }
@@ -44,13 +47,15 @@
 //!
 //! }
 //! ```
+//!
+//! Mixins written against [`GenericNode`] work with both the `dom` and `ssr` backends, and all the
+//! built-in mixins render the same markup on the server as in the browser.
 use crate::dom::Dom;
+use crate::generic_node::GenericNode;
 use futures_signals::signal::{Signal, SignalExt};
+use futures_util::FutureExt;
 use std::fmt::Display;
-#[cfg(feature = "dom")]
-use wasm_bindgen::JsCast;
-#[cfg(feature = "dom")]
-use web_sys::Element;
+use std::future::ready;
 
 pub trait Mixin<Target> {
     fn mixin(&self, node: &Dom);
@@ -83,51 +88,43 @@ where
 /// Note: This is a security risk if the string to be inserted might contain potentially malicious content.
 /// sanitize the content before it is inserted.
 /// See more: https://developer.mozilla.org/en-US/docs/Web/API/Element/innerHTML
-#[allow(unused_variables)]
 pub fn raw_html<'a>(text: &'a str) -> Box<dyn Fn(&Dom) -> () + 'a> {
-    let cb = move |node: &Dom| {
-        #[cfg(feature = "dom")]
-        {
-            let element = node.node().as_ref().clone().unchecked_into::<Element>();
-            element.set_inner_html(text);
-        }
+    let cb = move |dom: &Dom| {
+        dom.node().set_inner_html(text);
     };
     Box::new(cb)
 }
 
 /// A mixin that allows adding non-signal text
-#[allow(unused_variables)]
 pub fn raw_text<'a>(text: &'a str) -> Box<dyn Fn(&Dom) + 'a> {
     let cb = move |dom: &Dom| {
-        #[cfg(feature = "dom")]
-        {
-            dom.node().node.set_text_content(Some(&format!("{text}")));
-        }
+        dom.node().set_text_content(text);
     };
     Box::new(cb)
 }
 
 /// Mixin that adds text to a dom node
-#[allow(unused_variables)]
 pub fn text<T, S>(text: &S) -> Box<dyn Fn(&Dom)>
 where
     T: Display + 'static,
     S: Signal<Item = T> + SignalExt + Clone + 'static,
 {
     let signal = text.clone();
-    
-    let cb = move |_node: &Dom| {
-        #[cfg(feature = "dom")]
-        {
-            use std::future::ready;
-            let element = _node.node().as_ref().clone().unchecked_into::<Element>();
-            let signal = signal.clone();
-            let future = signal.for_each(move |value| {
-                element.set_text_content(Some(&format!("{}", value)));
-                ready(())
-            });
-            _node.effect(future);
-        }
+
+    let cb = move |dom: &Dom| {
+        let node = dom.node().clone();
+        let mut future = Box::pin(
+            signal
+                .clone()
+                .for_each(move |value| {
+                    node.set_text_content(&value.to_string());
+                    ready(())
+                })
+                .fuse(),
+        );
+        // Apply the current value right away, effects are not run on the server
+        let _ = (&mut future).now_or_never();
+        dom.effect(future);
     };
     Box::new(cb)
 }
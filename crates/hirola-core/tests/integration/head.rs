@@ -0,0 +1,45 @@
+use super::{document, test_div};
+use hirola::prelude::*;
+use wasm_bindgen_test::*;
+
+fn description() -> Option<String> {
+    document()
+        .query_selector(r#"meta[name="description"]"#)
+        .unwrap()
+        .and_then(|meta| meta.get_attribute("content"))
+}
+
+#[wasm_bindgen_test]
+fn nested_pages_restore_the_layout_entries() {
+    let layout = render_to(
+        html! {
+            <div>
+                <Title text="Layout" />
+                <Meta name="description" content="layout" />
+            </div>
+        },
+        &test_div(),
+    )
+    .unwrap();
+    let page = render_to(
+        html! {
+            <div>
+                <Title text="Page" />
+                <Meta name="description" content="page" />
+            </div>
+        },
+        &test_div(),
+    )
+    .unwrap();
+    assert_eq!(document().title(), "Page");
+    assert_eq!(description().as_deref(), Some("page"));
+
+    drop(page);
+    assert_eq!(document().title(), "Layout");
+    assert_eq!(description().as_deref(), Some("layout"));
+
+    drop(layout);
+    // The title is kept until another page sets it
+    assert_eq!(document().title(), "Layout");
+    assert_eq!(description(), None);
+}
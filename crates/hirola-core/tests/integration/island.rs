@@ -0,0 +1,82 @@
+use super::{document, test_div};
+use hirola::prelude::*;
+use hirola_core::dom_test_utils::next_tick_with;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+#[island]
+#[component]
+fn Counter(initial: i32) -> Dom {
+    let count = Mutable::new(initial);
+    let increment = count.callback(|s| *s.lock_mut() += 1);
+    html! {
+        <button on:click=increment>{count}</button>
+    }
+}
+
+#[wasm_bindgen_test]
+fn hydrates_only_the_islands() {
+    let container = test_div().unchecked_into::<web_sys::Element>();
+    container.set_inner_html(
+        r#"<h1>Static</h1><hirola-island data-island="Counter" data-props="{&quot;initial&quot;:3}"><button>3</button></hirola-island>"#,
+    );
+    let heading = document().query_selector("h1").unwrap().unwrap();
+
+    let islands = island::hydrate::<Counter>().unwrap();
+    assert_eq!(islands.len(), 1);
+
+    // The static content is left untouched
+    assert!(heading.is_connected());
+    let button = document()
+        .query_selector("hirola-island button")
+        .unwrap()
+        .unwrap()
+        .unchecked_into::<web_sys::HtmlElement>();
+    button.click();
+    next_tick_with(&button, |button| {
+        assert_eq!(button.text_content().unwrap(), "4");
+    });
+}
+
+#[wasm_bindgen_test]
+fn skips_islands_with_invalid_props() {
+    let container = test_div().unchecked_into::<web_sys::Element>();
+    container.set_inner_html(
+        r#"<hirola-island data-island="Counter" data-props="oops"><button>3</button></hirola-island>"#,
+    );
+    assert!(island::hydrate::<Counter>().unwrap().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn hydrating_an_app_replaces_the_server_markup() {
+    use hirola_core::prelude::history::MemoryHistory;
+
+    fn page(_: &App<()>) -> Dom {
+        html! {
+            <main>
+                <h1>"Static"</h1>
+                <Counter initial=3 />
+            </main>
+        }
+    }
+    let mut app = App::new(());
+    app.set_history(MemoryHistory::new("/"));
+    app.route("/", page);
+
+    // The markup rendered by the server for `page`
+    let container = test_div().unchecked_into::<web_sys::Element>();
+    container.set_inner_html(
+        r#"<main><h1>Static</h1><hirola-island data-island="Counter" data-props="{&quot;initial&quot;:3}" style="display: contents"><button>3</button></hirola-island></main>"#,
+    );
+    let server = container.inner_html();
+    let elements = container.query_selector_all("*").unwrap().length();
+
+    let handle = app.hydrate_to(&container);
+    assert_eq!(
+        container.query_selector_all("*").unwrap().length(),
+        elements
+    );
+    assert_eq!(container.inner_html(), server);
+    handle.unmount();
+    assert_eq!(container.inner_html(), "");
+}
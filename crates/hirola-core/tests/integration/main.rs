@@ -1,4 +1,8 @@
+pub mod head;
+#[cfg(feature = "serde")]
+pub mod island;
 pub mod keyed;
+pub mod node;
 pub mod non_keyed;
 pub mod router;
 
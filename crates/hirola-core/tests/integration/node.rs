@@ -0,0 +1,136 @@
+//! The same operations as `tests/ssr/node.rs`, checking the [`SsrNode`] semantics match the DOM.
+use hirola::prelude::*;
+use hirola_core::generic_node::DomNode;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+fn html(node: &DomNode) -> String {
+    match node.inner_element().dyn_into::<web_sys::Element>() {
+        Ok(element) => element.outer_html(),
+        Err(node) => node
+            .dyn_into::<web_sys::DocumentFragment>()
+            .map(|fragment| {
+                let div = DomNode::element("div");
+                div.inner_element()
+                    .append_child(&fragment.clone_node_with_deep(true).unwrap())
+                    .unwrap();
+                div.inner_element()
+                    .unchecked_into::<web_sys::Element>()
+                    .inner_html()
+            })
+            .unwrap(),
+    }
+}
+
+fn children(tags: &[&str]) -> (DomNode, Vec<DomNode>) {
+    let parent = DomNode::element("div");
+    let children = tags
+        .iter()
+        .map(|tag| {
+            let child = DomNode::element(tag);
+            parent.append_child(&child);
+            child
+        })
+        .collect();
+    (parent, children)
+}
+
+#[wasm_bindgen_test]
+fn appending_a_fragment_moves_its_children() {
+    let (parent, _) = children(&[]);
+    let fragment = DomNode::fragment();
+    let a = DomNode::element("a");
+    fragment.append_child(&a);
+    fragment.append_child(&DomNode::element("b"));
+
+    parent.append_child(&fragment);
+    assert_eq!(html(&parent), "<div><a></a><b></b></div>");
+    assert_eq!(html(&fragment), "");
+    assert_eq!(a.parent_node(), Some(parent));
+}
+
+#[wasm_bindgen_test]
+fn next_sibling_follows_the_parent_children() {
+    let (_parent, nodes) = children(&["a", "b"]);
+    assert_eq!(nodes[0].next_sibling(), Some(nodes[1].clone()));
+    assert_eq!(nodes[1].next_sibling(), None);
+    assert_eq!(DomNode::element("c").next_sibling(), None);
+}
+
+#[wasm_bindgen_test]
+fn inserting_moves_existing_nodes() {
+    let (parent, nodes) = children(&["a", "b", "c"]);
+    parent.insert_child_before(&nodes[2], Some(&nodes[0]));
+    assert_eq!(html(&parent), "<div><c></c><a></a><b></b></div>");
+
+    let other = DomNode::element("p");
+    other.append_child(&nodes[1]);
+    assert_eq!(html(&parent), "<div><c></c><a></a></div>");
+    assert_eq!(nodes[1].parent_node(), Some(other));
+
+    let fragment = DomNode::fragment();
+    fragment.append_child(&DomNode::element("i"));
+    nodes[0].insert_sibling_before(&fragment);
+    assert_eq!(html(&parent), "<div><c></c><i></i><a></a></div>");
+}
+
+#[wasm_bindgen_test]
+fn inserting_a_node_before_itself_does_nothing() {
+    let (parent, nodes) = children(&["a", "b"]);
+    parent.insert_child_before(&nodes[1], Some(&nodes[1]));
+    assert_eq!(html(&parent), "<div><a></a><b></b></div>");
+    nodes[0].insert_sibling_before(&nodes[0]);
+    assert_eq!(html(&parent), "<div><a></a><b></b></div>");
+}
+
+#[wasm_bindgen_test]
+fn replacing_a_marker_in_a_fragment() {
+    let parent = DomNode::fragment();
+    let marker = DomNode::marker();
+    parent.append_child(&DomNode::element("a"));
+    parent.append_child(&marker);
+    let fragment = DomNode::fragment();
+    fragment.append_child(&DomNode::element("b"));
+    fragment.append_child(&DomNode::element("c"));
+
+    parent.replace_child(&marker, &fragment);
+    assert_eq!(html(&parent), "<a></a><b></b><c></c>");
+    assert_eq!(marker.parent_node(), None);
+}
+
+#[wasm_bindgen_test]
+fn removing_nodes_clears_their_parent() {
+    let (parent, nodes) = children(&["a", "b", "c"]);
+    nodes[1].remove_self();
+    parent.remove_child(&nodes[2]);
+    assert_eq!(html(&parent), "<div><a></a></div>");
+    assert_eq!(nodes[1].parent_node(), None);
+    assert_eq!(nodes[2].parent_node(), None);
+
+    // Removing a detached node does nothing
+    nodes[1].remove_self();
+    parent.remove_child(&nodes[1]);
+    assert_eq!(html(&parent), "<div><a></a></div>");
+}
+
+#[wasm_bindgen_test]
+fn replacing_children() {
+    let (parent, nodes) = children(&["a", "b"]);
+    parent.replace_children_with(&DomNode::text_node("x"));
+    assert_eq!(html(&parent), "<div>x</div>");
+    assert_eq!(nodes[0].parent_node(), None);
+
+    parent.update_inner_text("y");
+    assert_eq!(html(&parent), "<div>y</div>");
+}
+
+#[wasm_bindgen_test]
+fn setting_inner_html_and_text_content() {
+    let (parent, nodes) = children(&["a"]);
+    parent.set_inner_html("<b>bold</b> &amp; <i>italic</i>");
+    assert_eq!(html(&parent), "<div><b>bold</b> &amp; <i>italic</i></div>");
+    assert_eq!(nodes[0].parent_node(), None);
+
+    parent.set_text_content("<b>");
+    assert_eq!(html(&parent), "<div>&lt;b&gt;</div>");
+}
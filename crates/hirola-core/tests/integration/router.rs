@@ -106,3 +106,142 @@ fn test_router_not_found() {
         not_found_dom.inner_html()
     );
 }
+
+#[wasm_bindgen_test]
+fn test_app_unmount_keeps_other_children() {
+    let mut app = App::new(AppState {});
+    app.set_history(hirola_core::prelude::history::MemoryHistory::new("/about"));
+    app.route("/about", about_page);
+    let parent = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("div")
+        .unwrap();
+    parent.set_inner_html("<p>Keep</p>");
+
+    let handle = app.mount_to(&parent);
+    assert_eq!(parent.inner_html(), "<p>Keep</p>About");
+    handle.unmount();
+    assert_eq!(parent.inner_html(), "<p>Keep</p>");
+
+    // Dropping the handle unmounts the app too
+    drop(app.mount_to(&parent));
+    assert_eq!(parent.inner_html(), "<p>Keep</p>");
+
+    // Forgetting the handle keeps the app mounted
+    app.mount_to(&parent).forget();
+    assert_eq!(parent.inner_html(), "<p>Keep</p>About");
+}
+
+#[wasm_bindgen_test]
+fn browser_history_strips_the_base_on_segment_boundaries() {
+    use hirola_core::prelude::history::{BrowserHistory, HistoryBackend};
+
+    let history = BrowserHistory::new("/app");
+    let set_path = |path: &str| {
+        web_sys::window()
+            .unwrap()
+            .history()
+            .unwrap()
+            .replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(path))
+            .unwrap();
+    };
+    set_path("/app");
+    assert_eq!(history.location(), "/");
+    set_path("/app/about");
+    assert_eq!(history.location(), "/about");
+    set_path("/application");
+    assert_eq!(history.location(), "/application");
+    set_path("/");
+}
+
+#[wasm_bindgen_test]
+fn link_keeps_its_other_classes() {
+    let mut app = App::new(AppState {});
+    app.set_history(hirola_core::prelude::history::MemoryHistory::new("/"));
+    let router = app.router().clone();
+    let nav = html! {
+        <nav>
+            <Link to="/about" router=router.clone() active_class="active" exact=true>"About"</Link>
+        </nav>
+    };
+    let anchor = nav.children().borrow()[0].node().clone();
+    anchor.set_attribute("class", "nav-link");
+
+    router.push("/about");
+    next_tick(move || {
+        // Keep the link and its effects alive
+        let _nav = &nav;
+        assert_eq!(
+            anchor.get_attribute("class").as_deref(),
+            Some("nav-link active")
+        );
+        router.push("/");
+        let anchor = anchor.clone();
+        next_tick(move || {
+            assert_eq!(anchor.get_attribute("class").as_deref(), Some("nav-link"));
+        });
+    });
+}
+
+#[wasm_bindgen_test]
+fn superseded_loads_are_dropped() {
+    use hirola_core::prelude::history::MemoryHistory;
+    use hirola_core::prelude::signal::SignalExt;
+    use std::{cell::RefCell, rc::Rc};
+
+    let slow = Mutable::new(false);
+    let fast = Mutable::new(false);
+    let mut app = App::new(AppState {});
+    app.set_history(MemoryHistory::new("/"));
+    app.route("/", home_page);
+    let loaded = slow.clone();
+    app.route_with_loader(
+        "/a",
+        move |_| loaded.signal().wait_for(true),
+        |_, _| Dom::text("A"),
+    );
+    let loaded = fast.clone();
+    app.route_with_loader(
+        "/b",
+        move |_| loaded.signal().wait_for(true),
+        |_, _| Dom::text("B"),
+    );
+    let navigated = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&navigated);
+    app.router()
+        .after_navigate(move |from, to| log.borrow_mut().push(format!("{from} -> {to}")));
+    let parent = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("div")
+        .unwrap();
+    let handle = Rc::new(app.mount_to(&parent));
+
+    // Navigate to /b while /a is still loading
+    let router = app.router().clone();
+    router.push("/a");
+    router.push("/b");
+    next_tick(move || {
+        assert_eq!(parent.inner_html(), "Home");
+        fast.set(true);
+        let parent = parent.clone();
+        let slow = slow.clone();
+        let navigated = Rc::clone(&navigated);
+        let handle = Rc::clone(&handle);
+        next_tick(move || {
+            assert_eq!(parent.inner_html(), "B");
+            // The stale load of /a is never mounted
+            slow.set(true);
+            let parent = parent.clone();
+            let navigated = Rc::clone(&navigated);
+            let _handle = Rc::clone(&handle);
+            next_tick(move || {
+                assert_eq!(parent.inner_html(), "B");
+                assert_eq!(*navigated.borrow(), ["/ -> /b"]);
+            });
+        });
+    });
+}
@@ -0,0 +1,74 @@
+//! Both the `dom` and `ssr` features are activated, native builds render with the SSR backend.
+use hirola::prelude::*;
+use hirola_core::generic_node::{backend, AnyNode, Backend, SsrNode};
+
+fn counter() -> Dom {
+    let count = Mutable::new(0);
+    let increment = count.callback(|count| *count.lock_mut() += 1);
+    html! {
+        <div>
+            <span>{count.clone()}</span>
+            <button on:click=increment>"+"</button>
+            <a href="/" on:click=|e: Event| e.prevent_default()>"Home"</a>
+        </div>
+    }
+}
+
+#[test]
+fn native_builds_use_the_ssr_backend() {
+    assert_eq!(backend(), Backend::Ssr);
+    let node = DomType::element("p");
+    assert!(matches!(node, AnyNode::Ssr(_)));
+    assert!(node.as_dom().is_none());
+}
+
+#[test]
+fn components_render_to_strings() {
+    assert_eq!(
+        render_to_string(counter()),
+        r#"<div><span>0</span><button>+</button><a href="/">Home</a></div>"#
+    );
+}
+
+#[test]
+fn nodes_wrap_ssr_nodes() {
+    let root = DomType::fragment();
+    let _dom = counter().mount(&root).unwrap();
+    let root: &SsrNode = root.as_ssr().unwrap();
+    assert_eq!(
+        root.query_selector("span").unwrap().unwrap().text_content(),
+        "0"
+    );
+}
+
+#[test]
+fn nodes_serialize_like_ssr_nodes() {
+    let root = DomType::fragment();
+    let _dom = counter().mount(&root).unwrap();
+    let ssr = root.as_ssr().unwrap();
+    assert_eq!(root.to_string(), ssr.to_string());
+    assert_eq!(root.to_html_node(), ssr.to_html_node());
+}
+
+#[test]
+fn apps_render_on_the_server() {
+    fn home(_: &App<()>) -> Dom {
+        html! {
+            <main>
+                <Title text="Home" />
+                {counter()}
+            </main>
+        }
+    }
+    let mut app = App::new(());
+    app.route("/", home);
+
+    let response = app.render_response("/");
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        response.head,
+        r#"<title data-hirola-head="title">Home</title>"#
+    );
+    assert!(response.body.starts_with("<main><div><span>0</span>"));
+    assert_eq!(app.render_response("/missing").status, 404);
+}
@@ -0,0 +1,64 @@
+use hirola::prelude::*;
+use hirola_core::generic_node::SsrNode;
+use hirola_core::prelude::island::Island;
+
+#[island]
+#[component]
+fn Counter(initial: i32, label: String) -> Dom {
+    let count = Mutable::new(initial);
+    html! {
+        <button>{label}" "{count}</button>
+    }
+}
+
+#[component]
+#[island]
+fn Greeting() -> Dom {
+    html! { <p>"Hello"</p> }
+}
+
+#[test]
+fn islands_render_with_their_props() {
+    let html = render_to_string(html! {
+        <main>
+            <h1>"Static"</h1>
+            <Counter initial=3 label="Clicks".to_string() />
+        </main>
+    });
+    assert_eq!(
+        html,
+        "<main><h1>Static</h1>\
+         <hirola-island data-island=\"Counter\" data-props=\"{&quot;initial&quot;:3,&quot;label&quot;:&quot;Clicks&quot;}\" style=\"display: contents\">\
+         <button>Clicks 3</button>\
+         </hirola-island></main>"
+    );
+}
+
+#[test]
+fn islands_without_props() {
+    let html = render_to_string(html! { <><Greeting /></> });
+    assert_eq!(
+        html,
+        "<hirola-island data-island=\"Greeting\" data-props=\"null\" style=\"display: contents\">\
+         <p>Hello</p></hirola-island>"
+    );
+}
+
+#[test]
+fn island_props_round_trip() {
+    let node = SsrNode::parse_html(&render_to_string(html! {
+        <><Counter initial=5 label="Likes".to_string() /></>
+    }))
+    .unwrap();
+    let island = node.query_selector("hirola-island").unwrap().unwrap();
+    assert_eq!(
+        island.get_attribute("data-island").as_deref(),
+        Some(Counter::NAME)
+    );
+    let props: Counter =
+        serde_json::from_str(&island.get_attribute("data-props").unwrap()).unwrap();
+    assert_eq!(
+        render_to_string(html! { <div>{props}</div> }),
+        render_to_string(html! { <div><Counter initial=5 label="Likes".to_string() /></div> })
+    );
+}
@@ -1,4 +1,19 @@
-use futures_signals::{signal::Mutable, signal_vec::MutableVec};
+#[cfg(feature = "serde")]
+mod island;
+mod node;
+mod parse;
+mod query;
+#[cfg(feature = "app")]
+mod pool;
+#[cfg(feature = "app")]
+mod router;
+#[cfg(feature = "tower")]
+mod service;
+
+use futures_signals::{
+    signal::{Mutable, Signal},
+    signal_vec::MutableVec,
+};
 use hirola::prelude::*;
 
 #[test]
@@ -30,6 +45,218 @@ fn reactive_text() {
     );
 }
 
+#[test]
+fn doctype() {
+    let node = html! {
+        <!DOCTYPE html>
+        <html>
+            <body>"Hello"</body>
+        </html>
+    };
+
+    assert_eq!(
+        render_to_string(node),
+        "<!DOCTYPE html><html><body>Hello</body></html>"
+    );
+}
+
+#[test]
+fn attributes_keep_their_order() {
+    let node = html! {
+        <a href="/about" class="link" id="about" title="About">"About"</a>
+    };
+
+    assert_eq!(
+        render_to_string(node),
+        r#"<a href="/about" class="link" id="about" title="About">About</a>"#
+    );
+
+    let node = DomType::element("p");
+    node.set_attribute("id", "a");
+    node.set_attribute("class", "b");
+    node.set_attribute("id", "c");
+    node.remove_attribute("class");
+    node.set_attribute("class", "d");
+    assert_eq!(node.to_string(), r#"<p id="c" class="d"></p>"#);
+}
+
+#[test]
+fn void_elements_have_no_closing_tag() {
+    let node = html! {
+        <form>
+            <input type="checkbox" checked="true" disabled="" />
+            <br />
+            <img src="/logo.png" alt="Logo" />
+        </form>
+    };
+
+    assert_eq!(
+        render_to_string(node),
+        r#"<form><input type="checkbox" checked disabled><br><img src="/logo.png" alt="Logo"></form>"#
+    );
+}
+
+#[test]
+fn raw_text_elements_are_not_escaped() {
+    let node = html! {
+        <div>
+            <script>"if (a < b && c > d) { log(\"</script>\") }"</script>
+            <style>"a > b { content: \"&\" }"</style>
+            <p>"a < b"</p>
+        </div>
+    };
+
+    assert_eq!(
+        render_to_string(node),
+        concat!(
+            r#"<div><script>if (a < b && c > d) { log("<\/script>") }</script>"#,
+            r#"<style>a > b { content: "&" }</style><p>a &lt; b</p></div>"#
+        )
+    );
+}
+
+/// A signal yielding a single value, since `text` needs a `Clone` signal.
+#[derive(Clone)]
+struct Once(Option<&'static str>);
+
+impl Signal for Once {
+    type Item = &'static str;
+
+    fn poll_change(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<&'static str>> {
+        std::task::Poll::Ready(self.0.take())
+    }
+}
+
+#[test]
+fn mixins_render_on_the_server() {
+    let title = Once(Some("Draft"));
+    let node = html! {
+        <article>
+            <div mixin:identity=&raw_html("<p>Hello <b>world</b></p><br>") />
+            <p mixin:identity=&raw_text("<not html>") />
+            <h1 mixin:identity=&text(&title) />
+        </article>
+    };
+
+    assert_eq!(
+        render_to_string(node),
+        concat!(
+            "<article><div><p>Hello <b>world</b></p><br></div>",
+            "<p>&lt;not html></p><h1>Draft</h1></article>"
+        )
+    );
+}
+
+#[test]
+fn event_handlers_are_ignored() {
+    let count = Mutable::new(0);
+    let increment = count.callback(|count| *count.lock_mut() += 1);
+    let submit = |e: Event| e.prevent_default();
+    let node = html! {
+        <form on:submit=submit>
+            <button on:click=increment>{count.clone()}</button>
+        </form>
+    };
+
+    assert_eq!(render_to_string(node), "<form><button>0</button></form>");
+}
+
+// With the `dom` feature, `Event` is `web_sys::Event`, which needs a browser
+#[cfg(not(feature = "dom"))]
+#[test]
+fn server_events_can_be_passed_to_handlers() {
+    let count = Mutable::new(0);
+    let event = Event::new("click");
+    count.callback_with(|count, e| {
+        e.prevent_default();
+        *count.lock_mut() += 1;
+    })(event.clone());
+    assert_eq!(count.get(), 1);
+    assert_eq!(event.type_(), "click");
+    assert!(event.default_prevented());
+}
+
+#[test]
+fn render_to_writers() {
+    let page = || {
+        html! {
+            <ul class="list">
+                <li>"a < b"</li>
+                <li>"c"</li>
+            </ul>
+        }
+    };
+    let expected = r#"<ul class="list"><li>a &lt; b</li><li>c</li></ul>"#;
+
+    let mut html = Vec::new();
+    render_to_writer(page(), &mut html).unwrap();
+    assert_eq!(String::from_utf8(html).unwrap(), expected);
+
+    let mut html = Vec::new();
+    futures_executor::block_on(render_to_async_writer(page(), &mut html)).unwrap();
+    assert_eq!(String::from_utf8(html).unwrap(), expected);
+}
+
+#[test]
+fn render_to_string_pretty_indents_elements() {
+    let node = html! {
+        <!DOCTYPE html>
+        <html>
+            <body>
+                <h1>"Title"</h1>
+                <p>"Some " <b>"bold"</b> " text"</p>
+                <br />
+            </body>
+        </html>
+    };
+
+    assert_eq!(
+        render_to_string_pretty(node),
+        r#"<!DOCTYPE html>
+<html>
+  <body>
+    <h1>Title</h1>
+    <p>
+      Some 
+      <b>bold</b>
+       text
+    </p>
+    <br>
+  </body>
+</html>"#
+    );
+}
+
+#[test]
+fn render_document_fills_shell() {
+    use hirola_core::document::Shell;
+
+    let shell = Shell::new(
+        "<html><head><!--hirola-head--></head><body><!--hirola-body--><!--hirola-scripts--></body></html>",
+    )
+    .script("/app.js");
+    let page = || {
+        html! {
+            <main>
+                <Title text="Home" />
+                "<!--hirola-head-->"
+            </main>
+        }
+    };
+
+    assert_eq!(
+        render_document(&shell, page),
+        concat!(
+            r#"<html><head><title data-hirola-head="title">Home</title></head>"#,
+            r#"<body><main>&lt;!--hirola-head--></main><script type="module" src="/app.js"></script></body></html>"#
+        )
+    );
+    assert!(render_document(&Shell::default(), || html! { <p /> }).starts_with("<!DOCTYPE html>"));
+}
+
 #[test]
 fn check_effects() {
     let count = MutableVec::new_with_values(vec![1, 2, 3]);
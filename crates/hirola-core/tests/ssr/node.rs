@@ -0,0 +1,119 @@
+//! The [`SsrNode`] tree follows the DOM semantics, see `tests/integration/node.rs`.
+use hirola::prelude::*;
+use hirola_core::generic_node::SsrNode;
+
+fn children(tags: &[&str]) -> (SsrNode, Vec<SsrNode>) {
+    let parent = SsrNode::element("div");
+    let children = tags
+        .iter()
+        .map(|tag| {
+            let child = SsrNode::element(tag);
+            parent.append_child(&child);
+            child
+        })
+        .collect();
+    (parent, children)
+}
+
+#[test]
+fn appending_a_fragment_moves_its_children() {
+    let (parent, _) = children(&[]);
+    let fragment = SsrNode::fragment();
+    let a = SsrNode::element("a");
+    fragment.append_child(&a);
+    fragment.append_child(&SsrNode::element("b"));
+
+    parent.append_child(&fragment);
+    assert_eq!(parent.to_string(), "<div><a></a><b></b></div>");
+    assert_eq!(fragment.to_string(), "");
+    assert_eq!(a.parent_node(), Some(parent));
+}
+
+#[test]
+fn next_sibling_follows_the_parent_children() {
+    let (_parent, nodes) = children(&["a", "b"]);
+    assert_eq!(nodes[0].next_sibling(), Some(nodes[1].clone()));
+    assert_eq!(nodes[1].next_sibling(), None);
+    assert_eq!(SsrNode::element("c").next_sibling(), None);
+}
+
+#[test]
+fn inserting_moves_existing_nodes() {
+    let (parent, nodes) = children(&["a", "b", "c"]);
+    parent.insert_child_before(&nodes[2], Some(&nodes[0]));
+    assert_eq!(parent.to_string(), "<div><c></c><a></a><b></b></div>");
+
+    let other = SsrNode::element("p");
+    other.append_child(&nodes[1]);
+    assert_eq!(parent.to_string(), "<div><c></c><a></a></div>");
+    assert_eq!(nodes[1].parent_node(), Some(other));
+
+    let fragment = SsrNode::fragment();
+    fragment.append_child(&SsrNode::element("i"));
+    nodes[0].insert_sibling_before(&fragment);
+    assert_eq!(parent.to_string(), "<div><c></c><i></i><a></a></div>");
+}
+
+#[test]
+fn inserting_a_node_before_itself_does_nothing() {
+    let (parent, nodes) = children(&["a", "b"]);
+    parent.insert_child_before(&nodes[1], Some(&nodes[1]));
+    assert_eq!(parent.to_string(), "<div><a></a><b></b></div>");
+    nodes[0].insert_sibling_before(&nodes[0]);
+    assert_eq!(parent.to_string(), "<div><a></a><b></b></div>");
+}
+
+#[test]
+fn replacing_a_marker_in_a_fragment() {
+    let parent = SsrNode::fragment();
+    let marker = SsrNode::marker();
+    parent.append_child(&SsrNode::element("a"));
+    parent.append_child(&marker);
+    let fragment = SsrNode::fragment();
+    fragment.append_child(&SsrNode::element("b"));
+    fragment.append_child(&SsrNode::element("c"));
+
+    parent.replace_child(&marker, &fragment);
+    assert_eq!(parent.to_string(), "<a></a><b></b><c></c>");
+    assert_eq!(marker.parent_node(), None);
+}
+
+#[test]
+fn removing_nodes_clears_their_parent() {
+    let (parent, nodes) = children(&["a", "b", "c"]);
+    nodes[1].remove_self();
+    parent.remove_child(&nodes[2]);
+    assert_eq!(parent.to_string(), "<div><a></a></div>");
+    assert_eq!(nodes[1].parent_node(), None);
+    assert_eq!(nodes[2].parent_node(), None);
+
+    // Removing a detached node does nothing
+    nodes[1].remove_self();
+    parent.remove_child(&nodes[1]);
+    assert_eq!(parent.to_string(), "<div><a></a></div>");
+}
+
+#[test]
+fn replacing_children() {
+    let (parent, nodes) = children(&["a", "b"]);
+    parent.replace_children_with(&SsrNode::text_node("x"));
+    assert_eq!(parent.to_string(), "<div>x</div>");
+    assert_eq!(nodes[0].parent_node(), None);
+
+    parent.update_inner_text("y");
+    assert_eq!(parent.to_string(), "<div>y</div>");
+}
+
+#[test]
+fn setting_inner_html_and_text_content() {
+    let (parent, nodes) = children(&["a"]);
+    parent.set_inner_html("<b>bold</b> &amp; <i>italic</i>");
+    assert_eq!(
+        parent.to_string(),
+        "<div><b>bold</b> &amp; <i>italic</i></div>"
+    );
+    assert_eq!(nodes[0].parent_node(), None);
+
+    parent.set_text_content("<b>");
+    assert_eq!(parent.to_string(), "<div>&lt;b></div>");
+}
@@ -0,0 +1,134 @@
+use hirola::prelude::*;
+use hirola_core::generic_node::{HtmlNode, NodeKind, SsrNode};
+
+fn round_trip(html: &str) -> String {
+    SsrNode::parse_html(html).unwrap().to_string()
+}
+
+#[test]
+fn parse_round_trip() {
+    for html in [
+        "<!DOCTYPE html><html><head><title>Home</title></head><body></body></html>",
+        r#"<a href="/about" class="link">About</a>"#,
+        "<p>Hello <b>world</b><!--marker--></p>",
+        r#"<img src="/logo.png" alt="Logo"><br>"#,
+        "<input type=\"checkbox\" checked>",
+        "<p>Fish &amp; chips &lt;3</p>",
+        "<script>if (a < b && c) {}</script>",
+    ] {
+        assert_eq!(round_trip(html), html);
+    }
+}
+
+#[test]
+fn parse_is_forgiving() {
+    assert_eq!(
+        round_trip("<DIV ID=main>text</span></Div>"),
+        r#"<div id="main">text</div>"#
+    );
+    assert_eq!(
+        round_trip("<ul><li>one<li>two</ul>"),
+        "<ul><li>one<li>two</li></li></ul>"
+    );
+    assert_eq!(round_trip("<p a=1 a=2>"), r#"<p a="1"></p>"#);
+    assert_eq!(round_trip("<br/><span/>"), "<br><span></span>");
+    assert_eq!(
+        round_trip("<p title='a &quot;b&quot;'>"),
+        r#"<p title="a &quot;b&quot;"></p>"#
+    );
+    assert_eq!(round_trip("1 < 2"), "1 &lt; 2");
+}
+
+#[test]
+fn parse_non_ascii_input() {
+    assert_eq!(round_trip("<!abcdef\u{e9}>"), "");
+    assert_eq!(round_trip("<!é>"), "");
+    assert_eq!(
+        round_trip(r#"<p title="café">naïve 😀</p><!--ü-->"#),
+        r#"<p title="café">naïve 😀</p><!--ü-->"#
+    );
+}
+
+#[test]
+fn parse_nodes() {
+    let root = SsrNode::parse_html("<!-- note --><textarea>&lt;b&gt;</textarea>").unwrap();
+    assert_eq!(root.kind(), NodeKind::Fragment);
+    let children = root.children();
+    assert_eq!(children[0].kind(), NodeKind::Comment);
+    assert_eq!(children[0].text().as_deref(), Some(" note "));
+    assert_eq!(children[1].text_content(), "<b>");
+}
+
+#[test]
+fn parse_errors() {
+    for html in ["<!-- open", "<p class=\"open>", "<p", "</p"] {
+        assert!(SsrNode::parse_html(html).is_err(), "{html}");
+    }
+    let error = SsrNode::parse_html("<p>text<!--").unwrap_err();
+    assert_eq!(error.to_string(), "unterminated comment at position 7");
+}
+
+#[test]
+fn html_node_round_trip() {
+    let root = DomType::fragment();
+    let dom = html! {
+        <ul class="list">
+            <li data-id="1">"One"</li>
+        </ul>
+    };
+    dom.mount(&root).unwrap();
+
+    let tree = root.to_html_node();
+    assert_eq!(
+        tree,
+        HtmlNode::Fragment {
+            children: vec![HtmlNode::Element {
+                name: "ul".into(),
+                attributes: vec![("class".into(), "list".into())],
+                children: vec![HtmlNode::Element {
+                    name: "li".into(),
+                    attributes: vec![("data-id".into(), "1".into())],
+                    children: vec![HtmlNode::Text { text: "One".into() }],
+                }],
+            }],
+        }
+    );
+    assert_eq!(SsrNode::from(&tree).to_string(), root.to_string());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_tree() {
+    let root = SsrNode::parse_html(r#"<a href="/" class="active">Home</a><!--x-->"#).unwrap();
+    let json = serde_json::to_value(&root).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "type": "fragment",
+            "children": [
+                {
+                    "type": "element",
+                    "name": "a",
+                    "attributes": [["href", "/"], ["class", "active"]],
+                    "children": [{ "type": "text", "text": "Home" }]
+                },
+                { "type": "comment", "text": "x" }
+            ]
+        })
+    );
+    let node: SsrNode = serde_json::from_value(json).unwrap();
+    assert_eq!(node.to_string(), root.to_string());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_dom() {
+    let dom = html! { <p class="note">"Saved"</p> };
+    let json = serde_json::to_string(&dom).unwrap();
+    assert_eq!(
+        json,
+        r#"{"type":"element","name":"p","attributes":[["class","note"]],"children":[{"type":"text","text":"Saved"}]}"#
+    );
+    let dom: Dom = serde_json::from_str(&json).unwrap();
+    assert_eq!(render_to_string(dom), r#"<p class="note">Saved</p>"#);
+}
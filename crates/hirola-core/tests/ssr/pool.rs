@@ -0,0 +1,81 @@
+use futures_executor::block_on;
+use hirola::prelude::*;
+use hirola_core::app::pool::{RenderError, RenderPool};
+use hirola_core::document::Shell;
+use std::sync::Arc;
+
+fn home_page(_: &App<()>) -> Dom {
+    html! { <h1>"Home"</h1> }
+}
+
+fn gone_page(app: &App<()>) -> Dom {
+    app.set_status(410);
+    html! { <h1>"Gone"</h1> }
+}
+
+fn panic_page(_: &App<()>) -> Dom {
+    panic!("failed to render")
+}
+
+fn create_pool(threads: usize) -> RenderPool<()> {
+    RenderPool::new(threads, || {
+        let mut app = App::new(());
+        app.route("/", home_page);
+        app.route("/gone", gone_page);
+        app.route("/panic", panic_page);
+        app
+    })
+}
+
+#[test]
+fn renders_from_many_threads() {
+    let pool = create_pool(2);
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = pool.clone();
+            std::thread::spawn(move || block_on(pool.render_to_string("/")))
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap().unwrap(), "<h1>Home</h1>");
+    }
+}
+
+#[test]
+fn each_render_has_its_own_response() {
+    let pool = create_pool(1);
+    let gone = block_on(pool.render_response("/gone")).unwrap();
+    assert_eq!(gone.status, 410);
+    let home = block_on(pool.render_response("/")).unwrap();
+    assert_eq!(home.status, 200);
+    let missing = block_on(pool.render_response("/missing")).unwrap();
+    assert_eq!(missing.status, 404);
+}
+
+#[test]
+fn renders_documents() {
+    let pool = create_pool(1);
+    let shell = Arc::new(Shell::new("<body><!--hirola-body--></body>"));
+    let response = block_on(pool.render_document("/", shell)).unwrap();
+    assert_eq!(response.body, "<body><h1>Home</h1></body>");
+}
+
+#[test]
+fn panicking_pages_only_fail_their_render() {
+    let pool = create_pool(1);
+    assert_eq!(block_on(pool.render_to_string("/panic")), Err(RenderError));
+    assert_eq!(
+        block_on(pool.render_to_string("/")).unwrap(),
+        "<h1>Home</h1>"
+    );
+}
+
+#[test]
+fn renders_are_send() {
+    fn assert_send<T: Send>(_: &T) {}
+    let pool = create_pool(1);
+    let rendering = pool.render_to_string("/");
+    assert_send(&pool);
+    assert_send(&rendering);
+    assert_eq!(block_on(rendering).unwrap(), "<h1>Home</h1>");
+}
@@ -0,0 +1,91 @@
+use hirola::prelude::*;
+use hirola_core::generic_node::{ssr_node, NodeKind, SsrNode};
+
+fn page() -> SsrNode {
+    let root = DomType::fragment();
+    let dom = html! {
+        <main id="content">
+            <nav class="menu main-menu">
+                <a href="/" class="active">"Home"</a>
+                <a href="/about" lang="en-US">"About"</a>
+            </nav>
+            <article data-kind="post">
+                <h1>"Hello   world"</h1>
+                <p>"First " <b>"post"</b></p>
+                <a href="https://example.com/image.png">"Image"</a>
+            </article>
+        </main>
+    };
+    dom.mount(&root).unwrap();
+    ssr_node(&root).unwrap().clone()
+}
+
+fn texts(nodes: Vec<SsrNode>) -> Vec<String> {
+    nodes.iter().map(SsrNode::text_content).collect()
+}
+
+#[test]
+fn inspect_nodes() {
+    let root = page();
+    let main = &root.children()[0];
+    assert_eq!(main.kind(), NodeKind::Element);
+    assert_eq!(main.tag_name().as_deref(), Some("main"));
+    assert_eq!(
+        main.attributes(),
+        vec![("id".to_string(), "content".to_string())]
+    );
+    assert_eq!(main.children().len(), 2);
+
+    let title = &main.children()[1].children()[0].children()[0];
+    assert_eq!(title.kind(), NodeKind::Text);
+    assert_eq!(title.text().as_deref(), Some("Hello   world"));
+    assert_eq!(root.descendants().len(), 15);
+}
+
+#[test]
+fn query_selectors() {
+    let root = page();
+    let query = |selector| texts(root.query_selector_all(selector).unwrap());
+
+    assert_eq!(query("a"), vec!["Home", "About", "Image"]);
+    assert_eq!(query("nav > a"), vec!["Home", "About"]);
+    assert_eq!(query("main a.active"), vec!["Home"]);
+    assert_eq!(query("#content > article > a"), vec!["Image"]);
+    assert_eq!(query(".menu.main-menu a[lang|=en]"), vec!["About"]);
+    assert_eq!(query("a[href^=https][href$='.png']"), vec!["Image"]);
+    assert_eq!(
+        query("[data-kind=\"post\"] b, h1"),
+        vec!["Hello   world", "post"]
+    );
+    assert_eq!(query("article > b"), Vec::<String>::new());
+    assert_eq!(query("nav *").len(), 2);
+
+    let first = root.query_selector("article *").unwrap().unwrap();
+    assert_eq!(first.tag_name().as_deref(), Some("h1"));
+    assert!(root.query_selector("table").unwrap().is_none());
+}
+
+#[test]
+fn invalid_selectors_are_errors() {
+    let root = page();
+    for selector in ["", "a >", "a:hover", "[href", "a,", "[href~]"] {
+        assert!(root.query_selector(selector).is_err(), "{selector}");
+    }
+    assert_eq!(
+        root.query_selector("a:hover").unwrap_err().to_string(),
+        "invalid selector `a:hover` at position 1"
+    );
+}
+
+#[test]
+fn text_matchers() {
+    let root = page();
+    let title = root.find_by_text("Hello world").unwrap();
+    assert_eq!(title.tag_name().as_deref(), Some("h1"));
+    let paragraph = root.find_by_text("First post").unwrap();
+    assert_eq!(paragraph.tag_name().as_deref(), Some("p"));
+    assert!(root.find_by_text("First").is_none());
+
+    assert!(root.contains_text("First  post"));
+    assert!(!root.contains_text("Contact"));
+}
@@ -0,0 +1,495 @@
+use hirola::prelude::*;
+use hirola_core::document::Shell;
+use hirola_core::prelude::history::{HistoryBackend, MemoryHistory};
+
+fn home_page(_: &App<()>) -> Dom {
+    html! { <h1>"Home"</h1> }
+}
+
+fn about_page(_: &App<()>) -> Dom {
+    html! { <h1>"About"</h1> }
+}
+
+fn create_test_app(history: &MemoryHistory) -> App<()> {
+    let mut app = App::new(());
+    app.set_history(history.clone());
+    app.route("/", home_page);
+    app.route("/about", about_page);
+    app
+}
+
+#[test]
+fn memory_history_tracks_pushes() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    app.router().push("/about");
+    assert_eq!(history.entries(), vec!["/", "/about"]);
+    assert_eq!(history.location(), "/about");
+    assert_eq!(app.router().current(), "/about");
+}
+
+#[test]
+fn memory_history_back_and_forward_update_router() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    app.router().push("/about");
+    let fragment = DomType::fragment();
+    let _dom = app.router().clone().render(&app, &fragment);
+    assert_eq!(fragment.to_string(), "<h1>About</h1>");
+
+    history.back();
+    assert_eq!(app.router().current(), "/");
+
+    history.forward();
+    assert_eq!(app.router().current(), "/about");
+}
+
+#[test]
+fn router_only_removes_its_own_nodes() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    let fragment = DomType::fragment();
+    fragment.append_child(&DomType::element("header"));
+    let dom = app.router().clone().render(&app, &fragment);
+    fragment.append_child(&DomType::element("footer"));
+    assert_eq!(
+        fragment.to_string(),
+        "<header></header><h1>Home</h1><footer></footer>"
+    );
+
+    drop(dom);
+    assert_eq!(fragment.to_string(), "<header></header><footer></footer>");
+}
+
+#[test]
+fn memory_history_push_truncates_forward_entries() {
+    let history = MemoryHistory::new("/");
+    history.push("/a");
+    history.push("/b");
+    history.back();
+    history.push("/c");
+    assert_eq!(history.entries(), vec!["/", "/a", "/c"]);
+    assert_eq!(history.index(), 2);
+}
+
+fn nav(app: &App<()>) -> Dom {
+    let router = app.router().clone();
+    html! {
+        <nav>
+            <Link to="/" router=router.clone() active_class="active" exact=true>"Home"</Link>
+            <Link to="/about" router=router active_class="active" exact=false>"About"</Link>
+        </nav>
+    }
+}
+
+#[test]
+fn link_renders_active_state() {
+    let history = MemoryHistory::new("/about/team");
+    let mut app = create_test_app(&history);
+    app.route("/about/team", nav);
+    let html = app.render_to_string("/about/team");
+    assert!(html.starts_with("<nav><a href=\"/\">Home</a>"));
+    assert!(html.contains(r#"aria-current="page""#));
+    assert!(html.contains(r#"class="active""#));
+    assert!(html.ends_with(">About</a></nav>"));
+}
+
+fn user_page(_: &App<()>, name: String) -> Dom {
+    html! { <h1>{name}</h1> }
+}
+
+#[test]
+fn loader_route_is_awaited_when_rendering() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.route_with_loader(
+        "/users/:id",
+        |app: &App<()>| {
+            let id = app.router().current_params()["id"].clone();
+            async move { format!("User {id}") }
+        },
+        user_page,
+    );
+    assert_eq!(app.render_to_string("/users/42"), "<h1>User 42</h1>");
+    assert_eq!(app.render_to_string("/about"), "<h1>About</h1>");
+}
+
+#[test]
+fn before_navigate_can_cancel_navigation() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    app.router()
+        .before_navigate(|from, to| from != "/" || to != "/about");
+    app.router().push("/about");
+    assert_eq!(app.router().current(), "/");
+    assert_eq!(history.entries(), vec!["/"]);
+}
+
+#[test]
+fn on_leave_blocks_navigation_until_cleared() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    let dirty = Mutable::new(true);
+    let unsaved = dirty.clone();
+    app.router().on_leave(move || !unsaved.get());
+    app.router().push("/about");
+    assert_eq!(app.router().current(), "/");
+
+    dirty.set(false);
+    app.router().push("/about");
+    assert_eq!(app.router().current(), "/about");
+
+    // The guard is removed once the page is left
+    dirty.set(true);
+    app.router().push("/");
+    assert_eq!(app.router().current(), "/");
+}
+
+#[test]
+fn on_leave_restores_location_on_blocked_back() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    app.router().push("/about");
+    let _dom = app.router().clone().render(&app, &DomType::fragment());
+    app.router().on_leave(|| false);
+    history.back();
+    assert_eq!(app.router().current(), "/about");
+    assert_eq!(history.location(), "/about");
+    // The blocked move is undone without touching the stack
+    assert_eq!(history.entries(), vec!["/", "/about"]);
+    assert_eq!(history.index(), 1);
+}
+
+#[test]
+fn on_leave_keeps_forward_entries_on_blocked_back() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    app.router().push("/about");
+    let _dom = app.router().clone().render(&app, &DomType::fragment());
+    history.back();
+    app.router().on_leave(|| false);
+    history.forward();
+    assert_eq!(app.router().current(), "/");
+    assert_eq!(history.entries(), vec!["/", "/about"]);
+    assert_eq!(history.index(), 0);
+}
+
+#[test]
+fn server_renders_do_not_grow_the_history() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    for _ in 0..5 {
+        app.render_to_string("/about");
+    }
+    assert_eq!(history.entries(), vec!["/about"]);
+}
+
+fn gone_page(app: &App<()>) -> Dom {
+    app.set_status(410);
+    app.insert_header("Cache-Control", "no-store");
+    html! { <h1>"Gone"</h1> }
+}
+
+fn old_page(app: &App<()>) -> Dom {
+    app.redirect("/about");
+    html! { <></> }
+}
+
+#[test]
+fn render_response_reports_status_and_headers() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.route("/gone", gone_page);
+    app.route("/old", old_page);
+
+    let response = app.render_response("/about");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, "<h1>About</h1>");
+
+    let response = app.render_response("/missing");
+    assert_eq!(response.status, 404);
+    assert_eq!(response.body, "Not Found");
+
+    let response = app.render_response("/gone");
+    assert_eq!(response.status, 410);
+    assert_eq!(response.header("cache-control"), Some("no-store"));
+
+    let response = app.render_response("/old");
+    assert!(response.is_redirect());
+    assert_eq!(response.header("Location"), Some("/about"));
+
+    // Each render starts from a fresh response
+    assert_eq!(app.render_response("/").status, 200);
+}
+
+fn article_page(_: &App<()>) -> Dom {
+    html! {
+        <article>
+            <Title text="Site" />
+            <Meta name="description" content="A site" />
+            <Title text="Article & more" />
+            <Meta name="og:title" content="Article" />
+            <HeadLink rel="canonical" href="https://example.com/article" />
+            <h1>"Article"</h1>
+        </article>
+    }
+}
+
+#[test]
+fn head_entries_are_collected_and_deduplicated() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.route("/article", article_page);
+
+    let response = app.render_response("/article");
+    assert_eq!(response.body, "<article><h1>Article</h1></article>");
+    assert_eq!(
+        response.head,
+        concat!(
+            r#"<title data-hirola-head="title">Article &amp; more</title>"#,
+            r#"<meta data-hirola-head="meta:description" name="description" content="A site">"#,
+            r#"<meta data-hirola-head="meta:og:title" property="og:title" content="Article">"#,
+            r#"<link data-hirola-head="link:canonical" rel="canonical" href="https://example.com/article">"#
+        )
+    );
+    assert_eq!(app.render_response("/about").head, "");
+}
+
+#[test]
+fn render_document_includes_head_and_status() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.route("/article", article_page);
+    let shell = hirola_core::document::Shell::new(
+        "<head><!--hirola-head--></head><body><!--hirola-body--></body>",
+    );
+
+    let response = app.render_document("/article", &shell);
+    assert_eq!(response.status, 200);
+    assert!(response
+        .body
+        .starts_with(r#"<head><title data-hirola-head="title">Article &amp; more</title>"#));
+    assert!(response
+        .body
+        .ends_with("<body><article><h1>Article</h1></article></body>"));
+    assert_eq!(app.render_document("/missing", &shell).status, 404);
+}
+
+#[test]
+fn push_with_fragment_matches_route_path() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    app.router().push("/about#team");
+    assert_eq!(app.router().current(), "/about");
+    assert_eq!(history.location(), "/about#team");
+
+    // Moving between fragments of the same page keeps the route
+    app.router().push("/about#contact");
+    assert_eq!(app.router().current(), "/about");
+    assert_eq!(
+        history.entries(),
+        vec!["/", "/about#team", "/about#contact"]
+    );
+}
+
+#[test]
+fn routers_listen_independently_until_discarded() {
+    let history = MemoryHistory::new("/");
+    history.push("/about");
+    let first = create_test_app(&history);
+    let second = create_test_app(&history);
+    let first_dom = first.router().clone().render(&first, &DomType::fragment());
+    let _second_dom = second
+        .router()
+        .clone()
+        .render(&second, &DomType::fragment());
+
+    history.back();
+    assert_eq!(first.router().current(), "/");
+    assert_eq!(second.router().current(), "/");
+
+    drop(first_dom);
+    history.forward();
+    assert_eq!(first.router().current(), "/");
+    assert_eq!(second.router().current(), "/about");
+}
+
+struct Greeting(&'static str);
+
+fn greeting_page(app: &App<()>) -> Dom {
+    let greeting = app.data::<Greeting>().unwrap().0;
+    html! { <h1>{greeting}</h1> }
+}
+
+#[test]
+fn pages_read_app_data() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    assert!(app.data::<Greeting>().is_none());
+    app.insert_data(Greeting("Hello"));
+    app.insert_data(Greeting("Hi"));
+    app.route("/greet", greeting_page);
+    assert_eq!(app.render_to_string("/greet"), "<h1>Hi</h1>");
+}
+
+struct Blog;
+
+impl Plugin<()> for Blog {
+    fn install(&self, app: &mut App<()>) {
+        app.insert_data(Greeting("Welcome to the blog"));
+        app.route("/blog", greeting_page);
+    }
+}
+
+#[test]
+fn plugins_extend_the_app() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.plugin(Blog);
+    assert_eq!(
+        app.render_to_string("/blog"),
+        "<h1>Welcome to the blog</h1>"
+    );
+}
+
+#[cfg(feature = "serde")]
+mod embedded_state {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Session {
+        user: String,
+    }
+
+    fn profile_page(app: &App<Session>, bio: String) -> Dom {
+        let user = app.state().user.clone();
+        html! { <div><h1>{user}</h1><p>{bio}</p></div> }
+    }
+
+    fn create_app(app: &mut App<Session>, on_server: bool) {
+        app.set_history(MemoryHistory::new("/"));
+        let loader = move |app: &App<Session>| {
+            assert!(on_server, "the client should use the embedded data");
+            let id = app.router().current_params()["id"].clone();
+            async move { format!("Bio of {id}</script>") }
+        };
+        app.route_with_embedded_loader("/profiles/:id", loader, profile_page);
+    }
+
+    #[test]
+    fn client_restores_server_state() {
+        let mut server = App::new(Session {
+            user: "Jane".to_string(),
+        });
+        create_app(&mut server, true);
+        let response = server.render_with_state("/profiles/7");
+        let (html, script) = response.body.split_once("<script").unwrap();
+        assert_eq!(html, "<div><h1>Jane</h1><p>Bio of 7&lt;/script></p></div>");
+        assert!(!script.contains("</script>\"}"));
+        let json = script
+            .split_once('>')
+            .unwrap()
+            .1
+            .strip_suffix("</script>")
+            .unwrap();
+
+        let mut client = App::<Session>::from_state_json(json).unwrap();
+        create_app(&mut client, false);
+        assert_eq!(client.state(), server.state());
+        assert_eq!(client.render_to_string("/profiles/7"), html);
+    }
+}
+
+fn post_page(app: &App<()>) -> Dom {
+    let id = app.router().current_params()["id"].clone();
+    html! {
+        <article>
+            <Title text=id.clone() />
+            <h1>{id}</h1>
+        </article>
+    }
+}
+
+#[test]
+fn generate_static_skips_paths_leaving_the_output_directory() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.route("/posts/:id", post_page);
+    app.static_paths("/posts/:id", |_| {
+        vec!["/posts/..".to_string(), "/posts/../../escape".to_string()]
+    });
+
+    let root = std::env::temp_dir().join(format!("hirola-escape-{}", std::process::id()));
+    let out_dir = root.join("dist");
+    let generated = app
+        .generate_static(&out_dir, &Shell::default(), &["/./about", "//about"])
+        .unwrap();
+    assert_eq!(generated, vec!["/", "/about", "//about"]);
+    assert!(!root.join("escape").exists());
+    assert!(out_dir.join("about/index.html").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn generate_static_writes_routes_404_and_sitemap() {
+    let history = MemoryHistory::new("/");
+    let mut app = create_test_app(&history);
+    app.route("/posts/:id", post_page);
+    app.route("/drafts/:id", post_page);
+    app.route("/old", old_page);
+    app.static_paths("/posts/:id", |_| {
+        vec!["/posts/hello".to_string(), "/posts/world".to_string()]
+    });
+    app.set_site_url("https://example.com/");
+
+    let out_dir = std::env::temp_dir().join(format!("hirola-static-{}", std::process::id()));
+    let shell =
+        Shell::new("<!DOCTYPE html><head><!--hirola-head--></head><body><!--hirola-body--></body>");
+    let generated = app
+        .generate_static(&out_dir, &shell, &["/drafts/secret"])
+        .unwrap();
+    assert_eq!(
+        generated,
+        vec![
+            "/",
+            "/about",
+            "/posts/hello",
+            "/posts/world",
+            "/old",
+            "/drafts/secret"
+        ]
+    );
+
+    let read = |file: &str| std::fs::read_to_string(out_dir.join(file)).unwrap();
+    let document = |body: &str| format!("<!DOCTYPE html><head></head><body>{body}</body>");
+    assert_eq!(read("index.html"), document("<h1>Home</h1>"));
+    assert_eq!(read("about/index.html"), document("<h1>About</h1>"));
+    assert_eq!(
+        read("posts/world/index.html"),
+        concat!(
+            r#"<!DOCTYPE html><head><title data-hirola-head="title">world</title></head>"#,
+            "<body><article><h1>world</h1></article></body>"
+        )
+    );
+    assert!(read("drafts/secret/index.html").contains("<h1>secret</h1>"));
+    assert!(read("old/index.html")
+        .contains(r#"<head><meta http-equiv="refresh" content="0; url=/about"></head>"#));
+    assert_eq!(read("404.html"), document("Not Found"));
+
+    let sitemap = read("sitemap.xml");
+    assert!(sitemap.contains("<loc>https://example.com/posts/hello</loc>"));
+    assert!(sitemap.contains("<loc>https://example.com/</loc>"));
+    assert!(!sitemap.contains("/old"));
+
+    std::fs::remove_dir_all(out_dir).unwrap();
+}
+
+#[test]
+fn render_to_string_works_inside_an_executor() {
+    let history = MemoryHistory::new("/");
+    let app = create_test_app(&history);
+    let html = futures_executor::block_on(async { app.render_to_string("/about") });
+    assert_eq!(html, "<h1>About</h1>");
+}
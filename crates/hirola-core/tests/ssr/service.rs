@@ -0,0 +1,135 @@
+use futures_executor::block_on;
+use hirola::prelude::*;
+use hirola_core::app::service::AppService;
+use hirola_core::document::Shell;
+use http::{request::Parts, Request, Response};
+use http_body_util::{BodyExt, Full};
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tower::{service_fn, ServiceExt};
+
+fn home_page(app: &App<String>) -> Dom {
+    let user = app.state().clone();
+    html! { <h1>{user}</h1> }
+}
+
+fn gone_page(app: &App<String>) -> Dom {
+    app.set_status(410);
+    html! { <h1>"Gone"</h1> }
+}
+
+fn create_app(parts: &Parts) -> App<String> {
+    let user = parts
+        .headers
+        .get("x-user")
+        .and_then(|user| user.to_str().ok())
+        .unwrap_or("guest");
+    let mut app = App::new(user.to_string());
+    app.route("/", home_page);
+    app.route("/gone", gone_page);
+    app
+}
+
+fn shell() -> Shell {
+    Shell::new("<body><!--hirola-body--></body>")
+}
+
+fn get(uri: &str) -> Request<()> {
+    Request::get(uri).body(()).unwrap()
+}
+
+async fn text<B: http_body::Body>(response: Response<B>) -> String
+where
+    B::Error: std::fmt::Debug,
+{
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[test]
+fn renders_routes_with_request_state() {
+    let service = AppService::new(create_app).shell(shell());
+
+    let request = Request::get("/").header("x-user", "jane").body(()).unwrap();
+    let response = block_on(service.clone().oneshot(request)).unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(block_on(text(response)), "<body><h1>jane</h1></body>");
+
+    let response = block_on(service.clone().oneshot(get("/gone"))).unwrap();
+    assert_eq!(response.status(), 410);
+
+    let response = block_on(service.clone().oneshot(get("/missing"))).unwrap();
+    assert_eq!(response.status(), 404);
+
+    let request = Request::post("/").body(()).unwrap();
+    let response = block_on(service.oneshot(request)).unwrap();
+    assert_eq!(response.status(), 405);
+}
+
+#[test]
+fn unmatched_paths_fall_through() {
+    let assets = service_fn(|request: Request<()>| async move {
+        let body = Full::new(format!("asset {}", request.uri().path()).into());
+        Ok::<_, Infallible>(Response::new(body))
+    });
+    let service = AppService::new(create_app).shell(shell()).fallback(assets);
+
+    let response = block_on(service.clone().oneshot(get("/app.js"))).unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(block_on(text(response)), "asset /app.js");
+
+    let response = block_on(service.oneshot(get("/"))).unwrap();
+    assert_eq!(block_on(text(response)), "<body><h1>guest</h1></body>");
+}
+
+#[test]
+fn renders_on_a_bounded_number_of_threads() {
+    let threads = Arc::new(Mutex::new(HashSet::new()));
+    let seen = Arc::clone(&threads);
+    let service = AppService::new(move |parts: &Parts| {
+        seen.lock().unwrap().insert(std::thread::current().id());
+        create_app(parts)
+    })
+    .threads(2)
+    .shell(shell());
+
+    let responses: Vec<_> = (0..8).map(|_| service.clone().oneshot(get("/"))).collect();
+    for response in responses {
+        let response = block_on(response).unwrap();
+        assert_eq!(block_on(text(response)), "<body><h1>guest</h1></body>");
+    }
+    assert!(threads.lock().unwrap().len() <= 2);
+}
+
+fn list_page(_: &App<String>) -> Dom {
+    let items: Vec<Dom> = (0..2000)
+        .map(|item| html! { <li>{item.to_string()}</li> })
+        .collect();
+    html! { <ul>{items}</ul> }
+}
+
+#[test]
+fn streams_the_page_in_chunks() {
+    let service = AppService::new(|parts: &Parts| {
+        let mut app = create_app(parts);
+        app.route("/list", list_page);
+        app
+    })
+    .shell(shell());
+
+    let response = block_on(service.oneshot(get("/list"))).unwrap();
+    let mut body = response.into_body();
+    let mut frames = Vec::new();
+    while let Some(frame) = block_on(body.frame()) {
+        frames.push(frame.unwrap().into_data().unwrap());
+    }
+    assert!(frames.len() > 2);
+    let html = String::from_utf8(frames.concat()).unwrap();
+    assert!(html.starts_with("<body><ul><li>0</li>"));
+    assert!(html.ends_with("<li>1999</li></ul></body>"));
+}
@@ -4,7 +4,8 @@ use std::{
     str::FromStr,
 };
 
-use hirola_core::prelude::{*, signal::SignalExt};
+use hirola_core::generic_node::dom_node;
+use hirola_core::prelude::{signal::SignalExt, *};
 use wasm_bindgen::JsCast;
 use web_sys::{Event, HtmlInputElement};
 
@@ -16,10 +17,14 @@ where
     <T as FromStr>::Err: Debug,
 {
     fn mixin(&self, node: &Dom) {
-        let input = {
-            let node = node.node().as_ref().clone();
-            node.dyn_into::<HtmlInputElement>().unwrap()
+        let Some(input) = dom_node(node.node()) else {
+            return;
         };
+        let input = input
+            .as_ref()
+            .clone()
+            .dyn_into::<HtmlInputElement>()
+            .unwrap();
         let signal = self.0.clone();
         node.effect(
             signal
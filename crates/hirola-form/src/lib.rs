@@ -1,5 +1,6 @@
 pub mod bind;
 
+use hirola_core::generic_node::dom_node;
 use hirola_core::prelude::{
     signal::{Mutable, MutableSignalRef, ReadOnlyMutable},
     Dom, GenericNode, Mixin, NodeRef,
@@ -92,10 +93,10 @@ impl<T: Serialize + DeserializeOwned + Clone> Mixin<Form> for Register<T, HtmlIn
             form.update_field(&name, new_value).unwrap();
         });
         dom.event("input", handler);
-        let input = {
-            let node = dom.node().clone();
-            node.dyn_into::<HtmlInputElement>().unwrap()
+        let Some(input) = dom_node(dom.node()) else {
+            return;
         };
+        let input = input.clone().dyn_into::<HtmlInputElement>().unwrap();
         let name = input.name();
         let value: String = self.form.get_value_by_field(&name).unwrap().unwrap();
         dom.node().set_attribute("value", &value);
@@ -3,7 +3,7 @@ use proc_macro_error::emit_error;
 use quote::quote;
 use syn::spanned::Spanned;
 
-pub fn create_function_component(f: syn::ItemFn) -> TokenStream {
+pub fn create_function_component(f: syn::ItemFn, island: bool) -> TokenStream {
     let struct_name = f.sig.ident;
     let (impl_generics, ty_generics, where_clause) = f.sig.generics.split_for_impl();
     let inputs = f.sig.inputs;
@@ -40,6 +40,40 @@ pub fn create_function_component(f: syn::ItemFn) -> TokenStream {
         )
     };
 
+    if island {
+        if !f.sig.generics.params.is_empty() {
+            emit_error!(f.sig.generics.span(), "Islands cannot be generic");
+        }
+        let name = struct_name.to_string();
+        return TokenStream::from(quote! {
+            #[derive(
+                ::hirola::prelude::island::serde::Serialize,
+                ::hirola::prelude::island::serde::Deserialize
+            )]
+            #[serde(crate = "::hirola::prelude::island::serde")]
+            #vis struct #struct_name #inputs_block
+
+            impl ::hirola::prelude::Island for #struct_name {
+                const NAME: &'static str = #name;
+
+                fn render_island(self: Box<Self>, dom: &Dom) -> Result<(), Error> {
+                    let result = {
+                        #inputs_reading
+                        #block
+                    };
+                    Box::new(result).render_into(&dom)?;
+                    Ok(())
+                }
+            }
+
+            impl ::hirola::prelude::Render for #struct_name {
+                fn render_into(self: Box<Self>, dom: &Dom) -> Result<(), Error> {
+                    ::hirola::prelude::island::render_island(self, dom)
+                }
+            }
+        });
+    }
+
     TokenStream::from(quote! {
         // #[derive(Debug)]
         #vis struct #struct_name #impl_generics #inputs_block
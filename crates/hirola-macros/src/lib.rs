@@ -86,11 +86,9 @@ fn node_to_tokens(node: Node) -> TokenStream {
                     })
                     .collect::<Vec<TokenStream>>();
                 if !node.children.is_empty() {
-                    let children_tokens = children_to_tokens(node.children);
+                    let children_tokens = fragment_to_tokens(node.children);
                     attributes.extend(vec![quote! {
-                        children: {
-                            Box::new(#children_tokens)
-                         }
+                        children: #children_tokens
                     }]);
                 }
 
@@ -105,6 +103,14 @@ fn node_to_tokens(node: Node) -> TokenStream {
             }
         }
         Node::Fragment(fragment) => tokens.extend(fragment_to_tokens(fragment.children)),
+        Node::Doctype(doctype) => {
+            let value = doctype.value.to_string_best();
+            tokens.extend(quote! {
+                ::hirola::prelude::Dom::new_from_node(
+                    &<::hirola::prelude::DomType as ::hirola::prelude::GenericNode>::doctype(#value),
+                )
+            });
+        }
         _ => {}
     }
     tokens
@@ -210,7 +216,7 @@ fn children_to_tokens(children: Vec<Node>) -> TokenStream {
     if !children.is_empty() {
         for child in children {
             match child {
-                Node::Element(_) => {
+                Node::Element(_) | Node::Fragment(_) => {
                     let node = node_to_tokens(child);
                     append_children.extend(quote! {
                         ::hirola::prelude::Dom::append_render(&mut template, #node );
@@ -235,7 +241,12 @@ fn children_to_tokens(children: Vec<Node>) -> TokenStream {
                         );
                     });
                 }
-                Node::Doctype(_) => {}
+                Node::Doctype(_) => {
+                    let node = node_to_tokens(child);
+                    append_children.extend(quote! {
+                        ::hirola::prelude::Dom::append_render(&mut template, #node);
+                    });
+                }
                 Node::Block(block) => match block {
                     NodeBlock::ValidBlock(block) => match braced_for_control(&block) {
                         Some(Control::ExprForLoop(ExprForLoop {
@@ -491,6 +502,36 @@ pub fn component(
     _attr: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let f = parse_macro_input!(item as syn::ItemFn);
-    component::create_function_component(f)
+    let mut f = parse_macro_input!(item as syn::ItemFn);
+    // `#[island]` below `#[component]` is still to be expanded
+    let island = take_attribute(&mut f, "island");
+    component::create_function_component(f, island)
+}
+
+/// Marks a component to be hydrated on its own, see `hirola::prelude::island`.
+///
+/// The props of the component are serialized into the server HTML, so they have to implement
+/// `Serialize` and `DeserializeOwned`. `#[component]` is implied and can be omitted.
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn island(
+    _attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let mut f = parse_macro_input!(item as syn::ItemFn);
+    take_attribute(&mut f, "component");
+    component::create_function_component(f, true)
+}
+
+/// Removes the attributes named `name` from `f`, returning whether there were any.
+fn take_attribute(f: &mut syn::ItemFn, name: &str) -> bool {
+    let is_named = |attr: &syn::Attribute| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name)
+    };
+    let found = f.attrs.iter().any(is_named);
+    f.attrs.retain(|attr| !is_named(attr));
+    found
 }
@@ -39,3 +39,44 @@ fn it_renders_component_with_props() {
     });
     assert_eq!("<p>hirola</p>", result);
 }
+
+#[component]
+fn Wrapper<R: Render + 'static>(children: R) -> Dom {
+    html! {
+        <section>{children}</section>
+    }
+}
+
+#[test]
+fn it_renders_component_with_children() {
+    let result = render_to_string({
+        html! {
+            <>
+                <Wrapper>
+                    <p>"child"</p>
+                </Wrapper>
+            </>
+        }
+    });
+    assert_eq!("<section><p>child</p></section>", result);
+}
+
+#[test]
+fn it_renders_component_with_mixed_children() {
+    let name = "world";
+    let result = render_to_string({
+        html! {
+            <>
+                <Wrapper>
+                    "Hello "
+                    <b>{name}</b>
+                    <>
+                        <i>"!"</i>
+                        "?"
+                    </>
+                </Wrapper>
+            </>
+        }
+    });
+    assert_eq!("<section>Hello <b>world</b><i>!</i>?</section>", result);
+}
@@ -34,6 +34,23 @@ pub mod app {
     pub use hirola_core::app::*;
 }
 
+/// Rendering complete HTML documents on the server.
+#[cfg(feature = "ssr")]
+pub mod document {
+    pub use hirola_core::document::*;
+}
+
+/// Head management with the title, meta and link components.
+pub mod head {
+    pub use hirola_core::head::*;
+}
+
+/// Islands, components hydrated on their own.
+#[cfg(feature = "serde")]
+pub mod island {
+    pub use hirola_core::island::*;
+}
+
 /// Include form mixins and utilities
 #[cfg(feature = "form")]
 #[cfg_attr(docsrs, doc(cfg(feature = "form")))]
@@ -33,7 +33,7 @@ fn router_pushes() {
     });
     let router = app.router().clone();
     let node = body();
-    let root = app.mount_to(&node);
+    app.mount_to(&node).forget();
     assert_eq!("<main>Main</main>", inner_html(&node));
     router.push("/page");
 
@@ -52,7 +52,7 @@ fn app_renders() {
     }
     let node = &body();
     app.route("/", test_app);
-    app.mount_to(&node);
+    app.mount_to(&node).forget();
     assert_eq!("<span>Test</span>", inner_html(&node));
 }
 